@@ -0,0 +1,168 @@
+//! CRC-CCITT carried in the CHK field of every frame: polynomial 0x1021,
+//! initial value 0xFFFF, no final XOR.
+
+use crate::error::SerializeError;
+
+const POLYNOMIAL: u16 = 0x1021;
+
+/// TABLES[k][x]: CRC update for byte `x` followed by `k` zero bytes
+const TABLES: [[u16; 256]; 4] = tables();
+
+const fn tables() -> [[u16; 256]; 4] {
+    let mut tables = [[0u16; 256]; 4];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = (i as u16) << 8;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ POLYNOMIAL
+            } else {
+                crc << 1
+            };
+            bit += 1;
+        }
+        tables[0][i] = crc;
+        i += 1;
+    }
+    let mut k = 1;
+    while k < 4 {
+        let mut i = 0;
+        while i < 256 {
+            let previous = tables[k - 1][i];
+            tables[k][i] = (previous << 8) ^ tables[0][(previous >> 8) as usize];
+            i += 1;
+        }
+        k += 1;
+    }
+    tables
+}
+
+/// Incremental CRC-CCITT, fed with consecutive parts of a frame
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct Crc16Ccitt(u16);
+
+impl Default for Crc16Ccitt {
+    fn default() -> Self {
+        Crc16Ccitt::new()
+    }
+}
+
+impl Crc16Ccitt {
+    pub const fn new() -> Crc16Ccitt {
+        Crc16Ccitt(0xFFFF)
+    }
+
+    /// Processes four bytes per step, then the remainder byte by byte
+    pub fn update(&mut self, bytes: &[u8]) {
+        let mut crc = self.0;
+        let mut chunks = bytes.chunks_exact(4);
+        for chunk in &mut chunks {
+            let high = ((crc >> 8) as u8 ^ chunk[0]) as usize;
+            let low = (crc as u8 ^ chunk[1]) as usize;
+            crc = TABLES[3][high]
+                ^ TABLES[2][low]
+                ^ TABLES[1][chunk[2] as usize]
+                ^ TABLES[0][chunk[3] as usize];
+        }
+        for &byte in chunks.remainder() {
+            crc = (crc << 8) ^ TABLES[0][((crc >> 8) as u8 ^ byte) as usize];
+        }
+        self.0 = crc;
+    }
+
+    /// CRC of the bytes processed so far
+    pub fn value(&self) -> u16 {
+        self.0
+    }
+}
+
+/// CRC-CCITT of `bytes`
+pub fn checksum(bytes: &[u8]) -> u16 {
+    let mut crc = Crc16Ccitt::new();
+    crc.update(bytes);
+    crc.value()
+}
+
+/// CHK at the end of `frame` matches the bytes before it. FRAMESIZE is not
+/// checked.
+pub fn verify_frame(frame: &[u8]) -> bool {
+    match frame.split_last_chunk::<2>() {
+        Some((bytes, chk)) => checksum(bytes) == u16::from_be_bytes(*chk),
+        None => false,
+    }
+}
+
+/// Writes FRAMESIZE from the length of `frame` and CHK over the bytes before
+/// it, e.g. after editing a frame in place
+pub fn finalize_frame(frame: &mut [u8]) -> Result<(), SerializeError> {
+    let framesize = u16::try_from(frame.len()).map_err(|_| SerializeError::SpaceExceeded)?;
+    //SYNC, FRAMESIZE and CHK at least
+    if frame.len() < 6 {
+        return Err(SerializeError::SpaceExceeded);
+    }
+    frame[2..4].copy_from_slice(&framesize.to_be_bytes());
+    let (bytes, chk) = frame.split_at_mut(frame.len() - 2);
+    chk.copy_from_slice(&checksum(bytes).to_be_bytes());
+    Ok(())
+}
+
+#[cfg(test)]
+mod checksum_test {
+    use super::*;
+
+    fn bitwise(bytes: &[u8]) -> u16 {
+        bytes.iter().fold(0xFFFF, |crc, &byte| {
+            (0..8).fold(crc ^ ((byte as u16) << 8), |crc, _| {
+                if crc & 0x8000 != 0 {
+                    (crc << 1) ^ POLYNOMIAL
+                } else {
+                    crc << 1
+                }
+            })
+        })
+    }
+
+    #[test]
+    fn matches_bitwise_crc() {
+        let bytes: [u8; 61] = core::array::from_fn(|i| (i as u8).wrapping_mul(151) ^ 0x5A);
+        for len in 0..bytes.len() {
+            assert_eq!(checksum(&bytes[..len]), bitwise(&bytes[..len]));
+        }
+        assert_eq!(checksum(b"123456789"), 0x29B1);
+    }
+
+    #[test]
+    fn incremental_update() {
+        let frame = [
+            0xAA, 0x41, 0x00, 0x12, 0x00, 0x3C, 0x48, 0x99, 0x90, 0x9A, 0x00, 0x34, 0x2E, 0xD5,
+            0x00, 0x05,
+        ];
+        for split in 0..frame.len() {
+            let mut crc = Crc16Ccitt::new();
+            crc.update(&frame[..split]);
+            crc.update(&frame[split..]);
+            assert_eq!(crc.value(), 0x168F);
+        }
+    }
+
+    #[test]
+    fn finalize_and_verify() {
+        let mut frame = [
+            0xAA, 0x41, 0x00, 0x00, 0x00, 0x3C, 0x48, 0x99, 0x90, 0x9A, 0x00, 0x34, 0x2E, 0xD5,
+            0x00, 0x05, 0x00, 0x00,
+        ];
+        assert!(!verify_frame(&frame));
+        assert_eq!(finalize_frame(&mut frame), Ok(()));
+        assert_eq!(
+            [frame[2], frame[3], frame[16], frame[17]],
+            [0x00, 0x12, 0x16, 0x8F]
+        );
+        assert!(verify_frame(&frame));
+        assert!(!verify_frame(&frame[..1]));
+        assert_eq!(
+            finalize_frame(&mut [0xAA, 0x41]),
+            Err(SerializeError::SpaceExceeded)
+        );
+    }
+}
@@ -0,0 +1,744 @@
+use super::{
+    count, AnalogChannel, AnalogKind, AnalogUnit, Config, DigitalChannel, DigitalUnit, Format,
+    Name, NominalFrequency, NumberFormat, PhasorChannel, PhasorKind, PhasorUnit, PmuConfig, Seq,
+};
+use crate::{
+    deserializer::{from_bytes, from_bytes_with_config, SynDeserializer},
+    i24, u24, ConfigParseError, DataType, Message, ParseError, SerializeError, SynSerializer,
+};
+use alloc::{string::String, vec::Vec};
+use serde::{
+    ser::{Error, SerializeStruct},
+    Serialize, Serializer,
+};
+
+/// Configuration of a PMU or PDC stream as carried by a CFG-3 frame.
+/// Extends the CFG-2 model with variable length UTF-8 names, scaling,
+/// location and measurement service information.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Config3 {
+    pub time_base: u24, //Resolution of FRACSEC time stamp
+    // One entry per PMU block, in the order the blocks appear in a data frame
+    pub pmus: Vec<PmuConfig3>,
+    pub data_rate: i16, //Frames per second, negative values are seconds per frame
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct PmuConfig3 {
+    pub station: String,    //STN: Station name, up to 255 bytes
+    pub idcode: u16,        //Data source ID number, identifies the source of each data block
+    pub g_pmu_id: [u8; 16], //Global PMU ID
+    pub format: Format,
+    pub phasors: Vec<PhasorChannel3>,
+    pub analogs: Vec<AnalogChannel3>,
+    pub digitals: Vec<DigitalChannel3>,
+    pub latitude: f32,  //PMU_LAT: WGS84 degrees, infinity if unspecified
+    pub longitude: f32, //PMU_LON: WGS84 degrees, infinity if unspecified
+    pub elevation: f32, //PMU_ELEV: Meters, infinity if unspecified
+    pub service_class: ServiceClass,
+    pub window: i32,      //Measurement window length in microseconds
+    pub group_delay: i32, //Measurement group delay in microseconds
+    pub fnom: NominalFrequency,
+    pub cfgcnt: u16, //Configuration change count
+}
+
+impl PmuConfig3 {
+    /// PHNMR: Number of phasors
+    pub fn phnmr(&self) -> usize {
+        self.phasors.len()
+    }
+
+    /// ANNMR: Number of analog values
+    pub fn annmr(&self) -> usize {
+        self.analogs.len()
+    }
+
+    /// DGNMR: Number of digital status words
+    pub fn dgnmr(&self) -> usize {
+        self.digitals.len()
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct PhasorChannel3 {
+    pub name: String,
+    pub scale: PhasorScale,
+}
+
+/// PHSCALE: Phasor type, modification flags and conversion factor
+#[derive(PartialEq, Debug, Clone)]
+pub struct PhasorScale {
+    pub modification: u16,          //Bits 31-16: Phasor modification flags
+    pub user: u8,                   //Bits 15-8: User designated
+    pub kind: PhasorKind,           //Bit 3
+    pub component: PhasorComponent, //Bits 2-0
+    pub magnitude: f32,             //Y: Scale factor, V or A per bit for integer data
+    pub angle: f32,                 //θ: Angle adjustment in radians
+}
+
+impl PhasorScale {
+    pub fn encode(&self) -> u32 {
+        let kind: u32 = match self.kind {
+            PhasorKind::Voltage => 0,
+            PhasorKind::Current => 1,
+        };
+        let component: u32 = match self.component {
+            PhasorComponent::ZeroSequence => 0,
+            PhasorComponent::PositiveSequence => 1,
+            PhasorComponent::NegativeSequence => 2,
+            PhasorComponent::PhaseA => 4,
+            PhasorComponent::PhaseB => 5,
+            PhasorComponent::PhaseC => 6,
+            PhasorComponent::Reserved(component) => (component & 0x07) as u32,
+        };
+        ((self.modification as u32) << 16) | ((self.user as u32) << 8) | (kind << 3) | component
+    }
+
+    pub fn decode(phasor_type: u32, magnitude: f32, angle: f32) -> Result<PhasorScale, ParseError> {
+        //Bits 7-4: Reserved
+        if (phasor_type & 0xF0) != 0 {
+            return Err(ParseError::ConfigParseError(
+                ConfigParseError::IncorrectReservedPhasorTypeBits,
+            ));
+        }
+        let kind = match (phasor_type >> 3) & 1 {
+            0 => PhasorKind::Voltage,
+            _ => PhasorKind::Current,
+        };
+        let component = match phasor_type & 0x07 {
+            0 => PhasorComponent::ZeroSequence,
+            1 => PhasorComponent::PositiveSequence,
+            2 => PhasorComponent::NegativeSequence,
+            4 => PhasorComponent::PhaseA,
+            5 => PhasorComponent::PhaseB,
+            6 => PhasorComponent::PhaseC,
+            component => PhasorComponent::Reserved(component as u8),
+        };
+        Ok(PhasorScale {
+            modification: (phasor_type >> 16) as u16,
+            user: (phasor_type >> 8) as u8,
+            kind,
+            component,
+            magnitude,
+            angle,
+        })
+    }
+}
+
+impl Serialize for PhasorScale {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("PhasorScale", 3)?;
+        state.serialize_field("phasor_type", &self.encode())?;
+        state.serialize_field("magnitude", &self.magnitude)?;
+        state.serialize_field("angle", &self.angle)?;
+        state.end()
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum PhasorComponent {
+    ZeroSequence,
+    PositiveSequence,
+    NegativeSequence,
+    PhaseA,
+    PhaseB,
+    PhaseC,
+    Reserved(u8), //3 or 7
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct AnalogChannel3 {
+    pub name: String,
+    pub scale: AnalogScale,
+}
+
+/// ANSCALE: Engineering value is `magnitude * X + offset`
+#[derive(PartialEq, Debug, Clone, Serialize)]
+pub struct AnalogScale {
+    pub magnitude: f32, //M: Scale factor
+    pub offset: f32,    //B: Offset
+}
+
+/// One digital status word: 16 channel names and their DIGUNIT mask words
+#[derive(PartialEq, Debug, Clone)]
+pub struct DigitalChannel3 {
+    pub names: [String; 16], //Bit 0 first
+    pub unit: DigitalUnit,
+}
+
+/// SVC_CLASS: Measurement service class as defined in IEEE Std C37.118.1
+#[derive(PartialEq, Debug, Clone)]
+pub enum ServiceClass {
+    Measurement, //M
+    Protection,  //P
+}
+
+impl ServiceClass {
+    pub fn encode(&self) -> u8 {
+        match self {
+            ServiceClass::Measurement => b'M',
+            ServiceClass::Protection => b'P',
+        }
+    }
+
+    pub fn decode(class: u8) -> Result<ServiceClass, ParseError> {
+        match class {
+            b'M' => Ok(ServiceClass::Measurement),
+            b'P' => Ok(ServiceClass::Protection),
+            _ => Err(ParseError::ConfigParseError(
+                ConfigParseError::UnknownServiceClass,
+            )),
+        }
+    }
+}
+
+impl Serialize for Config3 {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("Config3", 4)?;
+        //Bits 31-24 reserved for flags, set to zero
+        state.serialize_field("time_base", &self.time_base.encode())?;
+        state.serialize_field("num_pmu", &count::<S>(self.pmus.len())?)?;
+        state.serialize_field("pmus", &self.pmus)?;
+        state.serialize_field("data_rate", &self.data_rate)?;
+        state.end()
+    }
+}
+
+impl Serialize for PmuConfig3 {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("PmuConfig3", 22)?;
+        state.serialize_field("stn", &Utf8Name(&self.station))?;
+        state.serialize_field("idcode", &self.idcode)?;
+        state.serialize_field("g_pmu_id", &self.g_pmu_id)?;
+        state.serialize_field("format", &self.format.encode())?;
+        state.serialize_field("phnmr", &count::<S>(self.phnmr())?)?;
+        state.serialize_field("annmr", &count::<S>(self.annmr())?)?;
+        state.serialize_field("dgnmr", &count::<S>(self.dgnmr())?)?;
+        //CHNAM: Phasor names, then analog names, then 16 names per digital status word
+        state.serialize_field(
+            "phasor_chnam",
+            &Seq(self.phasors.iter().map(|p| Utf8Name(&p.name))),
+        )?;
+        state.serialize_field(
+            "analog_chnam",
+            &Seq(self.analogs.iter().map(|a| Utf8Name(&a.name))),
+        )?;
+        state.serialize_field(
+            "digital_chnam",
+            &Seq(self
+                .digitals
+                .iter()
+                .flat_map(|d| d.names.iter().map(|name| Utf8Name(name)))),
+        )?;
+        state.serialize_field("phscale", &Seq(self.phasors.iter().map(|p| &p.scale)))?;
+        state.serialize_field("anscale", &Seq(self.analogs.iter().map(|a| &a.scale)))?;
+        state.serialize_field("digunit", &Seq(self.digitals.iter().map(|d| &d.unit)))?;
+        state.serialize_field("pmu_lat", &self.latitude)?;
+        state.serialize_field("pmu_lon", &self.longitude)?;
+        state.serialize_field("pmu_elev", &self.elevation)?;
+        state.serialize_field("svc_class", &self.service_class.encode())?;
+        state.serialize_field("window", &self.window)?;
+        state.serialize_field("grp_dly", &self.group_delay)?;
+        state.serialize_field("fnom", &self.fnom.encode())?;
+        state.serialize_field("cfgcnt", &self.cfgcnt)?;
+        state.end()
+    }
+}
+
+/// Name preceded by its length in bytes
+struct Utf8Name<'a>(&'a str);
+
+impl<'a> Serialize for Utf8Name<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let len =
+            u8::try_from(self.0.len()).map_err(|_| S::Error::custom("name exceeds 255 bytes"))?;
+        let mut state = serializer.serialize_struct("Utf8Name", 2)?;
+        state.serialize_field("len", &len)?;
+        state.serialize_field("name", self.0)?;
+        state.end()
+    }
+}
+
+/// CFG-3 frame payload: a complete configuration, or one fragment of a
+/// configuration too large for a single frame.
+#[derive(PartialEq, Debug, Clone)]
+pub enum Cfg3Payload {
+    Config(Config3),
+    Fragment(Cfg3Fragment),
+}
+
+impl Serialize for Cfg3Payload {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("Cfg3Payload", 2)?;
+        match self {
+            Cfg3Payload::Config(config) => {
+                state.serialize_field("cont_idx", &0u16)?;
+                state.serialize_field("config", config)?;
+            }
+            Cfg3Payload::Fragment(fragment) => {
+                state.serialize_field("cont_idx", &fragment.cont_idx)?;
+                state.serialize_field("config", &Bytes(&fragment.bytes))?;
+            }
+        }
+        state.end()
+    }
+}
+
+/// Part of an encoded [`Config3`], starting with TIME_BASE in the first
+/// fragment and ending with DATA_RATE in the last
+#[derive(PartialEq, Debug, Clone)]
+pub struct Cfg3Fragment {
+    pub cont_idx: u16, //1 for the first fragment, counting up, 0xFFFF for the last
+    pub bytes: Vec<u8>,
+}
+
+impl Cfg3Fragment {
+    pub const LAST: u16 = 0xFFFF;
+}
+
+struct Bytes<'a>(&'a [u8]);
+
+impl<'a> Serialize for Bytes<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+impl Config3 {
+    /// Splits the configuration into fragments carrying at most `max_bytes`
+    /// of configuration each. A configuration that fits is returned whole.
+    pub fn split(&self, max_bytes: usize) -> Result<Vec<Cfg3Payload>, SerializeError> {
+        if max_bytes == 0 {
+            return Err(SerializeError::SpaceExceeded);
+        }
+        let bytes = SynSerializer::new(Vec::new()).encode(self)?;
+        if bytes.len() <= max_bytes {
+            return Ok(alloc::vec![Cfg3Payload::Config(self.clone())]);
+        }
+        let chunks = bytes.chunks(max_bytes);
+        let last = chunks.len();
+        if last >= Cfg3Fragment::LAST as usize {
+            return Err(SerializeError::SpaceExceeded);
+        }
+        Ok(chunks
+            .enumerate()
+            .map(|(index, chunk)| {
+                let cont_idx = if index + 1 == last {
+                    Cfg3Fragment::LAST
+                } else {
+                    index as u16 + 1
+                };
+                Cfg3Payload::Fragment(Cfg3Fragment {
+                    cont_idx,
+                    bytes: chunk.to_vec(),
+                })
+            })
+            .collect())
+    }
+}
+
+/// CFG-2 view of a CFG-3 configuration, to decode data frames of a stream
+/// configured with CFG-3. Names are cut to 16 bytes at a character boundary
+/// and the CFG-3 only fields are dropped. CFG-3 does not carry the analog
+/// kind, which is set to point-on-wave.
+///
+/// PHUNIT is PHSCALE's magnitude rounded to 10^-5 V or A per bit, and is
+/// left at 0 for floating point phasors. ANUNIT is ANSCALE's magnitude, which
+/// must be a whole number. Scales that CFG-2 cannot express, including phasor
+/// angle adjustments and analog offsets, fail with
+/// [`ConfigParseError::UnrepresentableScale`].
+impl TryFrom<&Config3> for Config {
+    type Error = ParseError;
+
+    fn try_from(config: &Config3) -> Result<Config, ParseError> {
+        Ok(Config {
+            time_base: config.time_base.clone(),
+            pmus: config
+                .pmus
+                .iter()
+                .map(PmuConfig::try_from)
+                .collect::<Result<_, _>>()?,
+            data_rate: config.data_rate,
+        })
+    }
+}
+
+impl TryFrom<&PmuConfig3> for PmuConfig {
+    type Error = ParseError;
+
+    fn try_from(pmu: &PmuConfig3) -> Result<PmuConfig, ParseError> {
+        let unrepresentable =
+            || ParseError::ConfigParseError(ConfigParseError::UnrepresentableScale);
+        let phasors = pmu
+            .phasors
+            .iter()
+            .map(|phasor| {
+                let scale = &phasor.scale;
+                let factor = match pmu.format.phasor {
+                    NumberFormat::Float => 0.0,
+                    NumberFormat::Int => libm::roundf(scale.magnitude * 1e5),
+                };
+                //Also rejects NaN
+                if scale.angle != 0.0
+                    || !(0.0..=0xFF_FFFF as f32).contains(&factor)
+                    || (factor == 0.0 && pmu.format.phasor == NumberFormat::Int)
+                {
+                    return Err(unrepresentable());
+                }
+                Ok(PhasorChannel {
+                    name: name(&phasor.name),
+                    unit: PhasorUnit {
+                        kind: scale.kind.clone(),
+                        factor: u24::new(factor as u32)?,
+                    },
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        let analogs = pmu
+            .analogs
+            .iter()
+            .map(|analog| {
+                let scale = &analog.scale;
+                if scale.offset != 0.0 || libm::truncf(scale.magnitude) != scale.magnitude {
+                    return Err(unrepresentable());
+                }
+                Ok(AnalogChannel {
+                    name: name(&analog.name),
+                    unit: AnalogUnit {
+                        kind: AnalogKind::PointOnWave,
+                        factor: i24::new(scale.magnitude as i32).map_err(|_| unrepresentable())?,
+                    },
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(PmuConfig {
+            station: name(&pmu.station),
+            idcode: pmu.idcode,
+            format: pmu.format.clone(),
+            phasors,
+            analogs,
+            digitals: pmu
+                .digitals
+                .iter()
+                .map(|digital| DigitalChannel {
+                    names: core::array::from_fn(|bit| name(&digital.names[bit])),
+                    unit: digital.unit.clone(),
+                })
+                .collect(),
+            fnom: pmu.fnom.clone(),
+            cfgcnt: pmu.cfgcnt,
+        })
+    }
+}
+
+/// First 16 bytes of a CFG-3 name, padded with spaces
+fn name(name: &str) -> Name {
+    let mut len = name.len().min(16);
+    while !name.is_char_boundary(len) {
+        len -= 1;
+    }
+    let mut bytes = [b' '; 16];
+    bytes[..len].copy_from_slice(&name.as_bytes()[..len]);
+    Name::decode(bytes)
+}
+
+/// Reassembles fragmented CFG-3 frames.
+///
+/// Payloads are pushed in the order they are received; the configuration is
+/// returned once the last fragment arrives. [`Cfg3Assembler::decode`] does
+/// the same for whole frames, so a stream can be decoded frame by frame
+/// without handling fragments. Reassembled configurations are limited to
+/// [`Cfg3Assembler::MAX_SIZE`] bytes.
+#[derive(Debug, Default)]
+pub struct Cfg3Assembler {
+    bytes: Vec<u8>,
+    cont_idx: u16, //Index of the last fragment received, 0 if none
+    idcode: u16,   //Stream of the fragments received, for Cfg3Assembler::decode
+}
+
+impl Cfg3Assembler {
+    /// Largest reassembled configuration in bytes, so a stream of fragments
+    /// that never ends cannot exhaust memory. 1 MiB holds thousands of PMUs
+    /// with the longest names.
+    pub const MAX_SIZE: usize = 1 << 20;
+
+    pub fn new() -> Cfg3Assembler {
+        Cfg3Assembler::default()
+    }
+
+    /// Decodes a frame as [`from_bytes_with_config`] does, or [`from_bytes`]
+    /// without a configuration, and reassembles CFG-3 fragments. Gives `None`
+    /// while fragments are outstanding, then a CFG-3 message holding the
+    /// whole configuration, stamped with the time of the last fragment.
+    /// Other frames are passed through.
+    pub fn decode<'a>(
+        &mut self,
+        bytes: &'a [u8],
+        config: Option<&Config>,
+    ) -> Result<Option<Message<'a>>, ParseError> {
+        let message = match config {
+            Some(config) => from_bytes_with_config(bytes, config)?,
+            None => from_bytes(bytes)?,
+        };
+        let Message {
+            version,
+            idcode,
+            time,
+            data,
+        } = message;
+        let payload = match data {
+            DataType::Cfg3(payload @ Cfg3Payload::Fragment(_)) => payload,
+            data => {
+                return Ok(Some(Message {
+                    version,
+                    idcode,
+                    time,
+                    data,
+                }))
+            }
+        };
+        if self.cont_idx != 0 && idcode != self.idcode {
+            self.reset();
+            return Err(ParseError::ConfigParseError(
+                ConfigParseError::UnexpectedFragment,
+            ));
+        }
+        self.idcode = idcode;
+        let Some(config) = self.push(payload)? else {
+            return Ok(None);
+        };
+        time.check_fracsec(&config.time_base)?;
+        Ok(Some(Message {
+            version,
+            idcode,
+            time,
+            data: DataType::Cfg3(Cfg3Payload::Config(config)),
+        }))
+    }
+
+    pub fn push(&mut self, payload: Cfg3Payload) -> Result<Option<Config3>, ParseError> {
+        let fragment = match payload {
+            Cfg3Payload::Config(config) => {
+                self.reset();
+                return Ok(Some(config));
+            }
+            Cfg3Payload::Fragment(fragment) => fragment,
+        };
+
+        let in_sequence = match fragment.cont_idx {
+            Cfg3Fragment::LAST => self.cont_idx != 0,
+            cont_idx => cont_idx == self.cont_idx.wrapping_add(1),
+        };
+        if !in_sequence {
+            self.reset();
+            return Err(ParseError::ConfigParseError(
+                ConfigParseError::UnexpectedFragment,
+            ));
+        }
+        if self.bytes.len() + fragment.bytes.len() > Cfg3Assembler::MAX_SIZE {
+            self.reset();
+            return Err(ParseError::ConfigParseError(
+                ConfigParseError::FragmentsTooLarge,
+            ));
+        }
+        self.bytes.extend_from_slice(&fragment.bytes);
+
+        if fragment.cont_idx == Cfg3Fragment::LAST {
+            let mut deserializer = SynDeserializer::new(&self.bytes);
+            let config = deserializer.deserialize_config3();
+            let consumed = deserializer.is_empty();
+            self.reset();
+            match config {
+                Ok(_) if !consumed => Err(ParseError::ConfigParseError(
+                    ConfigParseError::TrailingBytes,
+                )),
+                config => config.map(Some),
+            }
+        } else {
+            self.cont_idx = fragment.cont_idx;
+            Ok(None)
+        }
+    }
+
+    fn reset(&mut self) {
+        self.bytes.clear();
+        self.cont_idx = 0;
+    }
+}
+
+#[cfg(test)]
+mod cfg3_test {
+    use super::*;
+
+    #[test]
+    fn phasor_scale_encoding() {
+        let scale = PhasorScale {
+            modification: 0x0001,
+            user: 0x80,
+            kind: PhasorKind::Current,
+            component: PhasorComponent::PhaseB,
+            magnitude: 1.0,
+            angle: 0.5,
+        };
+        assert_eq!(scale.encode(), 0x0001800D);
+        assert_eq!(PhasorScale::decode(0x0001800D, 1.0, 0.5), Ok(scale));
+        assert_eq!(
+            PhasorScale::decode(0x00000010, 1.0, 0.5),
+            Err(ParseError::ConfigParseError(
+                ConfigParseError::IncorrectReservedPhasorTypeBits
+            ))
+        );
+    }
+
+    #[test]
+    fn service_class_encoding() {
+        assert_eq!(ServiceClass::decode(b'P'), Ok(ServiceClass::Protection));
+        assert_eq!(ServiceClass::Measurement.encode(), b'M');
+        assert_eq!(
+            ServiceClass::decode(b'm'),
+            Err(ParseError::ConfigParseError(
+                ConfigParseError::UnknownServiceClass
+            ))
+        );
+    }
+
+    #[test]
+    fn assembler_rejects_fragments_out_of_order() {
+        let fragment = |cont_idx| {
+            Cfg3Payload::Fragment(Cfg3Fragment {
+                cont_idx,
+                bytes: alloc::vec![0x00],
+            })
+        };
+        let mut assembler = Cfg3Assembler::new();
+        assert_eq!(
+            assembler.push(fragment(Cfg3Fragment::LAST)),
+            Err(ParseError::ConfigParseError(
+                ConfigParseError::UnexpectedFragment
+            ))
+        );
+        assert_eq!(assembler.push(fragment(1)), Ok(None));
+        assert_eq!(
+            assembler.push(fragment(3)),
+            Err(ParseError::ConfigParseError(
+                ConfigParseError::UnexpectedFragment
+            ))
+        );
+        //Sequence restarts after an error
+        assert_eq!(assembler.push(fragment(1)), Ok(None));
+        assert_eq!(
+            assembler.push(fragment(Cfg3Fragment::LAST)),
+            Err(ParseError::IllegalAccess)
+        );
+    }
+
+    #[test]
+    fn assembler_limits_size() {
+        let fragment = |cont_idx, len| {
+            Cfg3Payload::Fragment(Cfg3Fragment {
+                cont_idx,
+                bytes: alloc::vec![0x00; len],
+            })
+        };
+        let mut assembler = Cfg3Assembler::new();
+        assert_eq!(
+            assembler.push(fragment(1, Cfg3Assembler::MAX_SIZE)),
+            Ok(None)
+        );
+        assert_eq!(
+            assembler.push(fragment(2, 1)),
+            Err(ParseError::ConfigParseError(
+                ConfigParseError::FragmentsTooLarge
+            ))
+        );
+        assert_eq!(assembler.push(fragment(1, 1)), Ok(None));
+    }
+
+    #[test]
+    fn config_from_config3() {
+        let phasor = |magnitude, angle| PhasorChannel3 {
+            name: "AÄÄÄÄÄÄÄÄ".into(),
+            scale: PhasorScale {
+                modification: 0,
+                user: 0,
+                kind: PhasorKind::Current,
+                component: PhasorComponent::PhaseA,
+                magnitude,
+                angle,
+            },
+        };
+        let analog = |magnitude, offset| AnalogChannel3 {
+            name: "ANALOG".into(),
+            scale: AnalogScale { magnitude, offset },
+        };
+        let config = |phasor, analog| Config3 {
+            time_base: u24::new(1_000_000).unwrap(),
+            pmus: alloc::vec![PmuConfig3 {
+                station: "STATION A 500KV BUS".into(),
+                idcode: 7,
+                g_pmu_id: [0; 16],
+                format: Format::decode(0x0004).unwrap(),
+                phasors: alloc::vec![phasor],
+                analogs: alloc::vec![analog],
+                digitals: alloc::vec![],
+                latitude: f32::INFINITY,
+                longitude: f32::INFINITY,
+                elevation: f32::INFINITY,
+                service_class: ServiceClass::Protection,
+                window: 0,
+                group_delay: 0,
+                fnom: NominalFrequency::Hz50,
+                cfgcnt: 3,
+            }],
+            data_rate: -5,
+        };
+
+        let converted =
+            Config::try_from(&config(phasor(0.0045776, 0.0), analog(-3.0, 0.0))).unwrap();
+        assert_eq!(converted.time_base, u24::new(1_000_000).unwrap());
+        assert_eq!(converted.data_rate, -5);
+        let pmu = &converted.pmus[0];
+        assert_eq!(pmu.station.as_str(), "STATION A 500KV");
+        assert_eq!((pmu.idcode, pmu.cfgcnt), (7, 3));
+        assert_eq!(pmu.format.encode(), 0x0004);
+        assert_eq!(pmu.fnom, NominalFrequency::Hz50);
+        //Ä is two bytes, the 16th byte is dropped with it
+        assert_eq!(pmu.phasors[0].name.as_str(), "AÄÄÄÄÄÄÄ");
+        assert_eq!(pmu.phasors[0].unit.kind, PhasorKind::Current);
+        assert_eq!(pmu.phasors[0].unit.factor.encode(), 458);
+        assert_eq!(pmu.analogs[0].unit.factor.value(), -3);
+
+        let unrepresentable = Err(ParseError::ConfigParseError(
+            ConfigParseError::UnrepresentableScale,
+        ));
+        for (phasor, analog) in [
+            (phasor(0.0045776, 0.1), analog(1.0, 0.0)),
+            (phasor(1e-6, 0.0), analog(1.0, 0.0)),
+            (phasor(1e3, 0.0), analog(1.0, 0.0)),
+            (phasor(f32::NAN, 0.0), analog(1.0, 0.0)),
+            (phasor(1.0, 0.0), analog(0.001, 0.0)),
+            (phasor(1.0, 0.0), analog(1.0, 1.0)),
+            (phasor(1.0, 0.0), analog(1e9, 0.0)),
+        ] {
+            assert_eq!(Config::try_from(&config(phasor, analog)), unrepresentable);
+        }
+    }
+}
@@ -0,0 +1,456 @@
+use crate::{i24, u24, ConfigParseError, ParseError};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "alloc")]
+use serde::ser::{Error, SerializeStruct};
+use serde::{Serialize, Serializer};
+
+#[cfg(feature = "alloc")]
+mod cfg3;
+#[cfg(feature = "alloc")]
+pub use cfg3::*;
+
+/// Configuration of a PMU or PDC stream as carried by a CFG-1 or CFG-2 frame.
+/// Needed to interpret the data frames of the stream.
+#[cfg(feature = "alloc")]
+#[derive(PartialEq, Debug, Clone)]
+pub struct Config {
+    pub time_base: u24, //Resolution of FRACSEC time stamp
+    // One entry per PMU block, in the order the blocks appear in a data frame
+    pub pmus: Vec<PmuConfig>,
+    pub data_rate: i16, //Frames per second, negative values are seconds per frame
+}
+
+#[cfg(feature = "alloc")]
+#[derive(PartialEq, Debug, Clone)]
+pub struct PmuConfig {
+    pub station: Name, //STN: Station name
+    pub idcode: u16,   //Data source ID number, identifies the source of each data block
+    pub format: Format,
+    pub phasors: Vec<PhasorChannel>,
+    pub analogs: Vec<AnalogChannel>,
+    pub digitals: Vec<DigitalChannel>,
+    pub fnom: NominalFrequency,
+    pub cfgcnt: u16, //Configuration change count
+}
+
+#[cfg(feature = "alloc")]
+impl PmuConfig {
+    /// PHNMR: Number of phasors
+    pub fn phnmr(&self) -> usize {
+        self.phasors.len()
+    }
+
+    /// ANNMR: Number of analog values
+    pub fn annmr(&self) -> usize {
+        self.analogs.len()
+    }
+
+    /// DGNMR: Number of digital status words
+    pub fn dgnmr(&self) -> usize {
+        self.digitals.len()
+    }
+
+    /// Size in bytes of this PMU's block in a data frame
+    pub fn data_size(&self) -> usize {
+        2 + //STAT
+            self.phnmr() * 2 * self.format.phasor.size() + //PHASORS
+            2 * self.format.freq.size() + //FREQ, DFREQ
+            self.annmr() * self.format.analog.size() + //ANALOG
+            self.dgnmr() * 2 //DIGITAL
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Serialize for Config {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("Config", 4)?;
+        //Bits 31-24 reserved for flags, set to zero
+        state.serialize_field("time_base", &self.time_base.encode())?;
+        state.serialize_field("num_pmu", &count::<S>(self.pmus.len())?)?;
+        state.serialize_field("pmus", &self.pmus)?;
+        state.serialize_field("data_rate", &self.data_rate)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Serialize for PmuConfig {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("PmuConfig", 16)?;
+        state.serialize_field("stn", &self.station)?;
+        state.serialize_field("idcode", &self.idcode)?;
+        state.serialize_field("format", &self.format.encode())?;
+        state.serialize_field("phnmr", &count::<S>(self.phnmr())?)?;
+        state.serialize_field("annmr", &count::<S>(self.annmr())?)?;
+        state.serialize_field("dgnmr", &count::<S>(self.dgnmr())?)?;
+        //CHNAM: Phasor names, then analog names, then 16 names per digital status word
+        state.serialize_field("phasor_chnam", &Seq(self.phasors.iter().map(|p| &p.name)))?;
+        state.serialize_field("analog_chnam", &Seq(self.analogs.iter().map(|a| &a.name)))?;
+        state.serialize_field(
+            "digital_chnam",
+            &Seq(self.digitals.iter().flat_map(|d| d.names.iter())),
+        )?;
+        state.serialize_field("phunit", &Seq(self.phasors.iter().map(|p| p.unit.encode())))?;
+        state.serialize_field("anunit", &Seq(self.analogs.iter().map(|a| a.unit.encode())))?;
+        state.serialize_field("digunit", &Seq(self.digitals.iter().map(|d| &d.unit)))?;
+        state.serialize_field("fnom", &self.fnom.encode())?;
+        state.serialize_field("cfgcnt", &self.cfgcnt)?;
+        state.end()
+    }
+}
+
+/// Channel counts are 16-bit fields on the wire
+#[cfg(feature = "alloc")]
+fn count<S: Serializer>(len: usize) -> Result<u16, S::Error> {
+    u16::try_from(len).map_err(|_| S::Error::custom("channel count exceeds 65535"))
+}
+
+/// Serializes the items of an iterator as a sequence
+#[cfg(feature = "alloc")]
+struct Seq<I>(I);
+
+#[cfg(feature = "alloc")]
+impl<I> Serialize for Seq<I>
+where
+    I: Iterator + Clone,
+    I::Item: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(self.0.clone())
+    }
+}
+
+/// FORMAT: Data format in data frames
+#[derive(PartialEq, Debug, Clone)]
+pub struct Format {
+    pub freq: NumberFormat,              //Bit 3: FREQ/DFREQ
+    pub analog: NumberFormat,            //Bit 2: Analogs
+    pub phasor: NumberFormat,            //Bit 1: Phasors
+    pub phasor_notation: PhasorNotation, //Bit 0: Phasor format
+}
+
+impl Format {
+    pub fn encode(&self) -> u16 {
+        let mut format = 0u16;
+        if self.freq == NumberFormat::Float {
+            format |= 1 << 3;
+        }
+        if self.analog == NumberFormat::Float {
+            format |= 1 << 2;
+        }
+        if self.phasor == NumberFormat::Float {
+            format |= 1 << 1;
+        }
+        if self.phasor_notation == PhasorNotation::Polar {
+            format |= 1;
+        }
+        format
+    }
+
+    pub fn decode(format: u16) -> Result<Format, ParseError> {
+        //Bits 15-4: Unused
+        if (format & 0xFFF0) != 0 {
+            return Err(ParseError::ConfigParseError(
+                ConfigParseError::IncorrectReservedFormatBits,
+            ));
+        }
+        let number_format = |bit: u16| match (format >> bit) & 1 {
+            0 => NumberFormat::Int,
+            _ => NumberFormat::Float,
+        };
+        Ok(Format {
+            freq: number_format(3),
+            analog: number_format(2),
+            phasor: number_format(1),
+            phasor_notation: match format & 1 {
+                0 => PhasorNotation::Rectangular,
+                _ => PhasorNotation::Polar,
+            },
+        })
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum NumberFormat {
+    Int,   //16-bit integer
+    Float, //32-bit IEEE floating point
+}
+
+impl NumberFormat {
+    /// Size in bytes of a value
+    pub fn size(&self) -> usize {
+        match self {
+            NumberFormat::Int => 2,
+            NumberFormat::Float => 4,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum PhasorNotation {
+    Rectangular, //Real and imaginary
+    Polar,       //Magnitude and angle
+}
+
+/// CHNAM/STN: 16 byte ASCII name, padded with spaces
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct Name([u8; 16]);
+
+impl Name {
+    pub fn new(name: &str) -> Result<Name, ParseError> {
+        if name.len() > 16 || !name.is_ascii() {
+            return Err(ParseError::TypeRangeOverflow);
+        }
+        let mut bytes = [b' '; 16];
+        bytes[..name.len()].copy_from_slice(name.as_bytes());
+        Ok(Name(bytes))
+    }
+
+    pub fn encode(&self) -> [u8; 16] {
+        self.0
+    }
+
+    /// Keeps the bytes as received, including any non-ASCII characters
+    pub fn decode(name: [u8; 16]) -> Name {
+        Name(name)
+    }
+
+    /// Name without trailing padding
+    pub fn as_str(&self) -> &str {
+        let name = core::str::from_utf8(&self.0).unwrap_or_default();
+        name.trim_end_matches([' ', '\0'])
+    }
+}
+
+impl Serialize for Name {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(&self.0)
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct PhasorChannel {
+    pub name: Name,
+    pub unit: PhasorUnit,
+}
+
+/// PHUNIT: Conversion factor for integer phasor channels
+#[derive(PartialEq, Debug, Clone)]
+pub struct PhasorUnit {
+    pub kind: PhasorKind,
+    pub factor: u24, //Scaling in 10^-5 V or A per bit, ignored for floating point data
+}
+
+impl PhasorUnit {
+    pub fn encode(&self) -> u32 {
+        //Most significant byte: 0 voltage, 1 current
+        let kind: u32 = match self.kind {
+            PhasorKind::Voltage => 0,
+            PhasorKind::Current => 1,
+        };
+        (kind << 24) | self.factor.encode()
+    }
+
+    pub fn decode(unit: u32) -> Result<PhasorUnit, ParseError> {
+        let kind = match unit >> 24 {
+            0 => PhasorKind::Voltage,
+            1 => PhasorKind::Current,
+            _ => {
+                return Err(ParseError::ConfigParseError(
+                    ConfigParseError::UnknownPhasorUnit,
+                ))
+            }
+        };
+        Ok(PhasorUnit {
+            kind,
+            factor: u24::new(unit & 0x00FF_FFFF)?,
+        })
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum PhasorKind {
+    Voltage,
+    Current,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct AnalogChannel {
+    pub name: Name,
+    pub unit: AnalogUnit,
+}
+
+/// ANUNIT: Conversion factor for analog channels
+#[derive(PartialEq, Debug, Clone)]
+pub struct AnalogUnit {
+    pub kind: AnalogKind,
+    pub factor: i24, //User defined scaling
+}
+
+impl AnalogUnit {
+    pub fn encode(&self) -> u32 {
+        let kind: u32 = match self.kind {
+            AnalogKind::PointOnWave => 0,
+            AnalogKind::Rms => 1,
+            AnalogKind::Peak => 2,
+            AnalogKind::Other(kind) => kind as u32,
+        };
+        (kind << 24) | self.factor.encode()
+    }
+
+    pub fn decode(unit: u32) -> AnalogUnit {
+        let kind = match unit >> 24 {
+            0 => AnalogKind::PointOnWave,
+            1 => AnalogKind::Rms,
+            2 => AnalogKind::Peak,
+            kind => AnalogKind::Other(kind as u8),
+        };
+        AnalogUnit {
+            kind,
+            factor: i24::decode(unit),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum AnalogKind {
+    PointOnWave, //Single point-on-wave
+    Rms,         //RMS of analog input
+    Peak,        //Peak of analog input
+    Other(u8),   //Reserved (3-64) or user definable (65-255)
+}
+
+/// One digital status word: 16 channel names and their DIGUNIT mask words
+#[derive(PartialEq, Debug, Clone)]
+pub struct DigitalChannel {
+    pub names: [Name; 16], //Bit 0 first
+    pub unit: DigitalUnit,
+}
+
+/// DIGUNIT: Mask words for a digital status word
+#[derive(PartialEq, Debug, Clone, Serialize)]
+pub struct DigitalUnit {
+    pub normal: u16, //Normal status of the digital inputs
+    pub valid: u16,  //Current valid inputs to the PMU
+}
+
+/// FNOM: Nominal line frequency
+#[derive(PartialEq, Debug, Clone)]
+pub enum NominalFrequency {
+    Hz60,
+    Hz50,
+}
+
+impl NominalFrequency {
+    pub fn hz(&self) -> u16 {
+        match self {
+            NominalFrequency::Hz60 => 60,
+            NominalFrequency::Hz50 => 50,
+        }
+    }
+
+    pub fn encode(&self) -> u16 {
+        match self {
+            NominalFrequency::Hz60 => 0,
+            NominalFrequency::Hz50 => 1,
+        }
+    }
+
+    pub fn decode(fnom: u16) -> Result<NominalFrequency, ParseError> {
+        //Bits 15-1: Reserved
+        match fnom {
+            0 => Ok(NominalFrequency::Hz60),
+            1 => Ok(NominalFrequency::Hz50),
+            _ => Err(ParseError::ConfigParseError(
+                ConfigParseError::IncorrectReservedFnomBits,
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod config_test {
+    use super::*;
+
+    #[test]
+    fn name_padding() {
+        let name = Name::new("VA").unwrap();
+        assert_eq!(name.encode(), *b"VA              ");
+        assert_eq!(name.as_str(), "VA");
+    }
+
+    #[test]
+    fn name_exceeds_allowed_size() {
+        assert_eq!(
+            Name::new("STATION A 500KV BUS"),
+            Err(ParseError::TypeRangeOverflow)
+        );
+        assert_eq!(Name::new("STATIÖN"), Err(ParseError::TypeRangeOverflow));
+    }
+
+    #[test]
+    fn format_encoding() {
+        let format = Format {
+            freq: NumberFormat::Float,
+            analog: NumberFormat::Int,
+            phasor: NumberFormat::Float,
+            phasor_notation: PhasorNotation::Polar,
+        };
+        assert_eq!(format.encode(), 0x000B);
+        assert_eq!(Format::decode(0x000B), Ok(format));
+        assert_eq!(
+            Format::decode(0x0010),
+            Err(ParseError::ConfigParseError(
+                ConfigParseError::IncorrectReservedFormatBits
+            ))
+        );
+    }
+
+    #[test]
+    fn unit_encoding() {
+        let phunit = PhasorUnit {
+            kind: PhasorKind::Current,
+            factor: u24::new(45776).unwrap(),
+        };
+        assert_eq!(phunit.encode(), 0x0100B2D0);
+        assert_eq!(PhasorUnit::decode(0x0100B2D0), Ok(phunit));
+        assert_eq!(
+            PhasorUnit::decode(0x0200B2D0),
+            Err(ParseError::ConfigParseError(
+                ConfigParseError::UnknownPhasorUnit
+            ))
+        );
+
+        let anunit = AnalogUnit {
+            kind: AnalogKind::Other(65),
+            factor: i24::new(-1).unwrap(),
+        };
+        assert_eq!(anunit.encode(), 0x41FFFFFF);
+        assert_eq!(AnalogUnit::decode(0x41FFFFFF), anunit);
+    }
+
+    #[test]
+    fn fnom_encoding() {
+        assert_eq!(NominalFrequency::decode(1), Ok(NominalFrequency::Hz50));
+        assert_eq!(NominalFrequency::Hz60.encode(), 0);
+        assert_eq!(
+            NominalFrequency::decode(0x8000),
+            Err(ParseError::ConfigParseError(
+                ConfigParseError::IncorrectReservedFnomBits
+            ))
+        );
+    }
+}
@@ -0,0 +1,221 @@
+use crate::checksum;
+#[cfg(feature = "alloc")]
+use crate::{
+    config::{Cfg3Assembler, Config},
+    DataType, Message, ParseError,
+};
+
+//SYNC, FRAMESIZE, IDCODE, SOC, FRACSEC and CHK
+const MIN_FRAMESIZE: usize = 16;
+
+/// Cuts a byte stream into frames, for transports such as TCP that deliver
+/// arbitrary chunks. Bytes are buffered in a caller supplied buffer, which
+/// limits the largest frame that can be received.
+///
+/// Bytes that do not start a frame with a valid SYNC, FRAMESIZE and CHK are
+/// skipped until the next 0xAA sync byte and counted in [`FrameDecoder::discarded`].
+///
+/// With the `alloc` feature, [`FrameDecoder::next_message`] also decodes the
+/// frames and reassembles fragmented CFG-3 frames.
+pub struct FrameDecoder<'b> {
+    buffer: &'b mut [u8],
+    len: usize,      //Bytes held in buffer
+    returned: usize, //Length of the frame last returned, removed on the next call
+    discarded: usize,
+    #[cfg(feature = "alloc")]
+    cfg3: Cfg3Assembler,
+}
+
+impl<'b> FrameDecoder<'b> {
+    pub fn new(buffer: &'b mut [u8]) -> FrameDecoder<'b> {
+        FrameDecoder {
+            buffer,
+            len: 0,
+            returned: 0,
+            discarded: 0,
+            #[cfg(feature = "alloc")]
+            cfg3: Cfg3Assembler::new(),
+        }
+    }
+
+    /// Buffers as much of `bytes` as fits and returns the number of bytes taken.
+    /// Remaining bytes are pushed again after frames have been taken out.
+    pub fn push(&mut self, bytes: &[u8]) -> usize {
+        self.release();
+        let taken = bytes.len().min(self.buffer.len() - self.len);
+        self.buffer[self.len..self.len + taken].copy_from_slice(&bytes[..taken]);
+        self.len += taken;
+        taken
+    }
+
+    /// Next complete frame with a valid CHK, `None` if more bytes are needed.
+    /// The frame is passed to [`crate::deserializer::from_bytes`] to decode it.
+    pub fn next_frame(&mut self) -> Option<&[u8]> {
+        let framesize = self.next_framesize()?;
+        Some(&self.buffer[..framesize])
+    }
+
+    /// Next complete message, data frames being decoded with `config`.
+    /// CFG-3 fragments are reassembled and returned as one CFG-3 message
+    /// once the last fragment arrives. Frames that fail to decode give an
+    /// error and are dropped.
+    #[cfg(feature = "alloc")]
+    pub fn next_message(
+        &mut self,
+        config: Option<&Config>,
+    ) -> Option<Result<Message<'_>, ParseError>> {
+        loop {
+            let framesize = self.next_framesize()?;
+            if !is_cfg3_fragment(&self.buffer[..framesize]) {
+                return self
+                    .cfg3
+                    .decode(&self.buffer[..framesize], config)
+                    .transpose();
+            }
+            //The reassembled configuration is owned, it is moved into a
+            //message that does not borrow the buffer of the fragment
+            match self.cfg3.decode(&self.buffer[..framesize], config) {
+                Ok(None) => continue,
+                Ok(Some(Message {
+                    version,
+                    idcode,
+                    time,
+                    data: DataType::Cfg3(payload),
+                })) => {
+                    return Some(Ok(Message {
+                        version,
+                        idcode,
+                        time,
+                        data: DataType::Cfg3(payload),
+                    }))
+                }
+                Ok(Some(_)) => unreachable!("CFG-3 fragments reassemble to CFG-3"),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+
+    /// FRAMESIZE of the next complete frame at the start of the buffer
+    fn next_framesize(&mut self) -> Option<usize> {
+        self.release();
+        loop {
+            match self.buffer[..self.len].iter().position(|&b| b == 0xAA) {
+                Some(start) => self.discard(start),
+                None => {
+                    self.discard(self.len);
+                    return None;
+                }
+            }
+            if self.len < 4 {
+                return None;
+            }
+            let framesize = u16::from_be_bytes([self.buffer[2], self.buffer[3]]) as usize;
+            if !(MIN_FRAMESIZE..=self.buffer.len()).contains(&framesize) {
+                self.discard(1);
+                continue;
+            }
+            if self.len < framesize {
+                return None;
+            }
+            if checksum::verify_frame(&self.buffer[..framesize]) {
+                self.returned = framesize;
+                return Some(framesize);
+            }
+            self.discard(1);
+        }
+    }
+
+    /// Total number of bytes skipped while searching for frames
+    pub fn discarded(&self) -> usize {
+        self.discarded
+    }
+
+    /// Number of bytes buffered but not yet returned as a frame
+    pub fn buffered(&self) -> usize {
+        self.len - self.returned
+    }
+
+    fn release(&mut self) {
+        let returned = core::mem::take(&mut self.returned);
+        self.remove(returned);
+    }
+
+    fn discard(&mut self, count: usize) {
+        self.discarded += count;
+        self.remove(count);
+    }
+
+    fn remove(&mut self, count: usize) {
+        self.buffer.copy_within(count..self.len, 0);
+        self.len -= count;
+    }
+}
+
+/// CFG-3 frame with a non-zero CONT_IDX, which follows FRACSEC
+#[cfg(feature = "alloc")]
+fn is_cfg3_fragment(frame: &[u8]) -> bool {
+    //Bits 6-4 of SYNC: Frame type
+    (frame[1] >> 4) & 0x07 == 5 && frame.get(14..16).is_some_and(|cont_idx| cont_idx != [0, 0])
+}
+
+#[cfg(test)]
+mod decoder_test {
+    use super::*;
+    use crate::{deserializer::from_bytes, Command, DataType};
+
+    //Send CFG-2 command
+    const FRAME: [u8; 18] = [
+        0xAA, 0x41, 0x00, 0x12, 0x00, 0x3c, 0x48, 0x99, 0x90, 0x9a, 0x00, 0x34, 0x2e, 0xd5, 0x00,
+        0x05, 0x16, 0x8F,
+    ];
+
+    #[test]
+    fn frames_split_across_chunks() {
+        let mut buffer = [0u8; 32];
+        let mut decoder = FrameDecoder::new(&mut buffer);
+
+        assert_eq!(decoder.push(&FRAME[..5]), 5);
+        assert_eq!(decoder.next_frame(), None);
+        assert_eq!(decoder.push(&FRAME[5..]), 13);
+        assert_eq!(decoder.push(&FRAME), 14);
+        let frame = decoder.next_frame().unwrap();
+        assert_eq!(frame, FRAME);
+        assert_eq!(
+            from_bytes(frame).map(|m| m.data),
+            Ok(DataType::Cmd(Command::SendCfg2))
+        );
+
+        assert_eq!(decoder.push(&FRAME[14..]), 4);
+        assert_eq!(decoder.next_frame(), Some(&FRAME[..]));
+        assert_eq!(decoder.next_frame(), None);
+        assert_eq!(decoder.buffered(), 0);
+        assert_eq!(decoder.discarded(), 0);
+    }
+
+    #[test]
+    fn resynchronises_after_garbage() {
+        let mut buffer = [0u8; 64];
+        let mut decoder = FrameDecoder::new(&mut buffer);
+        let mut corrupted = FRAME;
+        corrupted[8] ^= 0x01;
+
+        decoder.push(&[0x00, 0xAA, 0x01]);
+        decoder.push(&corrupted);
+        decoder.push(&FRAME);
+        assert_eq!(decoder.next_frame(), Some(&FRAME[..]));
+        assert_eq!(decoder.discarded(), 3 + FRAME.len());
+    }
+
+    #[test]
+    fn skips_frames_larger_than_buffer() {
+        let mut buffer = [0u8; 20];
+        let mut decoder = FrameDecoder::new(&mut buffer);
+
+        decoder.push(&[0xAA, 0x31, 0x01, 0xC6]);
+        let taken = decoder.push(&FRAME);
+        assert_eq!(decoder.next_frame(), None);
+        decoder.push(&FRAME[taken..]);
+        assert_eq!(decoder.next_frame(), Some(&FRAME[..]));
+        assert_eq!(decoder.discarded(), 4);
+    }
+}
@@ -1,73 +1,936 @@
-use crate::{error::*, Frame, Message};
-use log::trace;
-use serde::{
-    de::{IntoDeserializer, SeqAccess},
-    Deserialize, Deserializer,
+#[cfg(feature = "alloc")]
+use crate::{
+    checksum,
+    config::*,
+    message::{DataFrame, Phasor, PmuData, Scalar},
+    u24, FrameHeader, Stat,
 };
+use crate::{checksum::Crc16Ccitt, error::*, Command, DataType, Frame, FrameType, Header, Message};
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec::Vec};
+use core::marker::PhantomData;
+use log::trace;
+use serde::{de::SeqAccess, Deserialize, Deserializer};
 
-pub fn from_bytes(bytes: &[u8]) -> Result<Message, ParseError> {
-    let mut deserializer = SynDeserializer::new(&bytes[..bytes.len() - 2]);
-    let frame = Frame::deserialize(&mut deserializer)?;
-    let checksum = bytes[bytes.len() - 2..]
-        .try_into()
-        .map_err(|_| ParseError::IllegalAccess)?;
-    let checksum = u16::from_be_bytes(checksum);
-    if checksum == deserializer.get_checksum() {
-        let message = frame.try_into()?;
-        Ok(message)
-    } else {
-        trace!("{:x}", deserializer.get_checksum());
-        Err(ParseError::InvalidChecksum)
+/// Input of [`SynDeserializer`]. Slices are decoded without copying, other
+/// sources read the frame as it is decoded.
+pub trait ByteSource<'de> {
+    /// Fills `buf` with the next bytes of the input
+    fn read(&mut self, buf: &mut [u8]) -> Result<(), ParseError>;
+
+    /// Next `len` bytes, borrowed for as long as the decoded message
+    fn read_borrowed(&mut self, len: usize) -> Result<&'de [u8], ParseError>;
+
+    /// Number of bytes left, if known before reading them
+    fn remaining(&self) -> Option<usize> {
+        None
+    }
+}
+
+impl<'de> ByteSource<'de> for &'de [u8] {
+    fn read(&mut self, buf: &mut [u8]) -> Result<(), ParseError> {
+        buf.copy_from_slice(self.read_borrowed(buf.len())?);
+        Ok(())
+    }
+
+    fn read_borrowed(&mut self, len: usize) -> Result<&'de [u8], ParseError> {
+        if len > self.len() {
+            return Err(ParseError::IllegalAccess);
+        }
+        let (bytes, rest) = self.split_at(len);
+        *self = rest;
+        Ok(bytes)
+    }
+
+    fn remaining(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+/// Part of a caller supplied buffer not yet handed out. Streams only keep
+/// the borrowed fields of a frame here, such as header text.
+#[cfg(any(feature = "std", feature = "embedded-io"))]
+struct Scratch<'de>(&'de mut [u8]);
+
+#[cfg(any(feature = "std", feature = "embedded-io"))]
+impl<'de> Scratch<'de> {
+    fn take(&mut self, len: usize) -> Result<&'de mut [u8], ParseError> {
+        if len > self.0.len() {
+            return Err(ParseError::SpaceExceeded);
+        }
+        let (taken, rest) = core::mem::take(&mut self.0).split_at_mut(len);
+        self.0 = rest;
+        Ok(taken)
+    }
+}
+
+/// Frame read from a [`std::io::Read`], such as a socket or serial port.
+/// Borrowed fields, header text and command EXTFRAME, are read into
+/// `scratch`, which may be empty when only data and configuration frames are
+/// expected.
+#[cfg(feature = "std")]
+pub struct IoSource<'de, R> {
+    reader: R,
+    scratch: Scratch<'de>,
+}
+
+#[cfg(feature = "std")]
+impl<'de, R: std::io::Read> IoSource<'de, R> {
+    pub fn new(reader: R, scratch: &'de mut [u8]) -> IoSource<'de, R> {
+        IoSource {
+            reader,
+            scratch: Scratch(scratch),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'de, R: std::io::Read> ByteSource<'de> for IoSource<'de, R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<(), ParseError> {
+        self.reader.read_exact(buf).map_err(|e| match e.kind() {
+            std::io::ErrorKind::UnexpectedEof => ParseError::IllegalAccess,
+            _ => ParseError::Io,
+        })
+    }
+
+    fn read_borrowed(&mut self, len: usize) -> Result<&'de [u8], ParseError> {
+        let bytes = self.scratch.take(len)?;
+        self.read(bytes)?;
+        Ok(bytes)
+    }
+}
+
+/// Frame read from an [`embedded_io::Read`], see [`IoSource`]
+#[cfg(feature = "embedded-io")]
+pub struct EmbeddedIoSource<'de, R> {
+    reader: R,
+    scratch: Scratch<'de>,
+}
+
+#[cfg(feature = "embedded-io")]
+impl<'de, R: embedded_io::Read> EmbeddedIoSource<'de, R> {
+    pub fn new(reader: R, scratch: &'de mut [u8]) -> EmbeddedIoSource<'de, R> {
+        EmbeddedIoSource {
+            reader,
+            scratch: Scratch(scratch),
+        }
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl<'de, R: embedded_io::Read> ByteSource<'de> for EmbeddedIoSource<'de, R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<(), ParseError> {
+        self.reader.read_exact(buf).map_err(|e| match e {
+            embedded_io::ReadExactError::UnexpectedEof => ParseError::IllegalAccess,
+            embedded_io::ReadExactError::Other(_) => ParseError::Io,
+        })
+    }
+
+    fn read_borrowed(&mut self, len: usize) -> Result<&'de [u8], ParseError> {
+        let bytes = self.scratch.take(len)?;
+        self.read(bytes)?;
+        Ok(bytes)
+    }
+}
+
+/// Decodes a single frame. Data frames cannot be interpreted without the
+/// configuration of the stream and fail with [`ParseError::ConfigNeeded`].
+pub fn from_bytes(bytes: &[u8]) -> Result<Message<'_>, ParseError> {
+    Ok(from_bytes_detailed(bytes)?)
+}
+
+/// [`from_bytes`] with the offset and name of the offending field on error
+pub fn from_bytes_detailed(bytes: &[u8]) -> Result<Message<'_>, DecodeError> {
+    decode(bytes, None)
+}
+
+/// Reads and decodes the next frame of `source`, checking CHK as the frame
+/// is read. The source is left after the frame, or inside it on error.
+pub fn from_source<'de, S: ByteSource<'de>>(source: S) -> Result<Message<'de>, ParseError> {
+    Ok(from_source_detailed(source)?)
+}
+
+/// [`from_source`] with the offset and name of the offending field on error
+pub fn from_source_detailed<'de, S: ByteSource<'de>>(
+    source: S,
+) -> Result<Message<'de>, DecodeError> {
+    decode(source, None)
+}
+
+/// Decodes a single frame, using `config` to interpret data frames.
+#[cfg(feature = "alloc")]
+pub fn from_bytes_with_config<'a>(
+    bytes: &'a [u8],
+    config: &Config,
+) -> Result<Message<'a>, ParseError> {
+    Ok(from_bytes_with_config_detailed(bytes, config)?)
+}
+
+/// [`from_bytes_with_config`] with the offset and name of the offending
+/// field on error
+#[cfg(feature = "alloc")]
+pub fn from_bytes_with_config_detailed<'a>(
+    bytes: &'a [u8],
+    config: &Config,
+) -> Result<Message<'a>, DecodeError> {
+    decode(bytes, Some(config))
+}
+
+/// Reads and decodes the next frame of `source`, using `config` to
+/// interpret data frames.
+#[cfg(feature = "alloc")]
+pub fn from_source_with_config<'de, S: ByteSource<'de>>(
+    source: S,
+    config: &Config,
+) -> Result<Message<'de>, ParseError> {
+    Ok(from_source_with_config_detailed(source, config)?)
+}
+
+/// [`from_source_with_config`] with the offset and name of the offending
+/// field on error
+#[cfg(feature = "alloc")]
+pub fn from_source_with_config_detailed<'de, S: ByteSource<'de>>(
+    source: S,
+    config: &Config,
+) -> Result<Message<'de>, DecodeError> {
+    decode(source, Some(config))
+}
+
+/// Configuration used by [`SynDeserializer::deserialize_message`] to
+/// interpret data frames. Without the `alloc` feature there is no `Config`
+/// and it is always `None`.
+#[cfg(feature = "alloc")]
+pub type ConfigRef<'c> = Option<&'c Config>;
+#[cfg(not(feature = "alloc"))]
+pub type ConfigRef<'c> = Option<&'c core::convert::Infallible>;
+
+/// Trims `bytes` to the FRAMESIZE of the frame it starts with, for captures
+/// carrying padding or further frames after it. The result is passed to
+/// [`from_bytes`], which otherwise rejects such input.
+pub fn trim_to_framesize(bytes: &[u8]) -> Result<&[u8], ParseError> {
+    let framesize = bytes.get(2..4).ok_or(ParseError::IllegalAccess)?;
+    let framesize = u16::from_be_bytes([framesize[0], framesize[1]]);
+    bytes
+        .get(..framesize as usize)
+        .ok_or(ParseError::BaseParseError(
+            BaseParseError::IncorrectFrameSize,
+        ))
+}
+
+fn decode<'de, S: ByteSource<'de>>(
+    source: S,
+    config: ConfigRef,
+) -> Result<Message<'de>, DecodeError> {
+    SynDeserializer::from_source(source)
+        .deserialize_message(config)
+        .inspect_err(|e| trace!("{}", e))
+}
+
+/// TIME_BASE of a configuration frame, otherwise of the configuration
+/// given for the stream
+#[cfg(feature = "alloc")]
+fn time_base<'c>(data: &'c DataType, config: ConfigRef<'c>) -> Option<&'c u24> {
+    match data {
+        DataType::Cfg1(config) | DataType::Cfg2(config) => Some(&config.time_base),
+        DataType::Cfg3(Cfg3Payload::Config(config)) => Some(&config.time_base),
+        _ => config.map(|config| &config.time_base),
     }
 }
-pub struct SynDeserializer<'de> {
-    bytes: &'de [u8],
+
+#[cfg(not(feature = "alloc"))]
+fn time_base<'c>(_data: &'c DataType, _config: ConfigRef<'c>) -> Option<&'c crate::u24> {
+    None
+}
+
+pub struct SynDeserializer<'de, S = &'de [u8]> {
+    source: S,
     index: usize,
-    sync: Option<u16>,
-    checksum: u16,
+    end: usize, //Offset of CHK, once FRAMESIZE is known
+    checksum: Crc16Ccitt,
+    field: &'static str, //Field being decoded
+    offset: usize,       //Offset of the field being decoded
+    marker: PhantomData<&'de [u8]>,
 }
 
 impl<'de> SynDeserializer<'de> {
-    pub fn new(bytes: &'de [u8]) -> SynDeserializer {
+    pub fn new(bytes: &'de [u8]) -> SynDeserializer<'de> {
+        SynDeserializer::from_source(bytes)
+    }
+}
+
+impl<'de, S: ByteSource<'de>> SynDeserializer<'de, S> {
+    pub fn from_source(source: S) -> SynDeserializer<'de, S> {
         SynDeserializer {
-            bytes,
+            source,
             index: 0,
-            sync: None,
-            checksum: 0xFF_FF,
+            end: usize::MAX,
+            checksum: Crc16Ccitt::new(),
+            field: "SYNC",
+            offset: 0,
+            marker: PhantomData,
+        }
+    }
+
+    /// Decodes the frame at the start of the input. Slices must hold exactly
+    /// one frame, other sources are read up to the end of the frame. Errors
+    /// carry the offset and name of the offending field.
+    pub fn deserialize_message(&mut self, config: ConfigRef) -> Result<Message<'de>, DecodeError> {
+        let frame = self.deserialize_frame(config).map_err(|e| self.error(e))?;
+
+        self.at("CHK");
+        let mut chk = [0u8; 2];
+        self.source.read(&mut chk).map_err(|e| self.error(e))?;
+        if u16::from_be_bytes(chk) != self.checksum.value() {
+            return Err(self.error(ParseError::InvalidChecksum));
+        }
+
+        let message: Message<'de> = frame.try_into().map_err(|e| {
+            //Raised while validating SYNC and FRACSEC
+            (self.field, self.offset) = match e {
+                ParseError::BaseParseError(
+                    BaseParseError::IncorrectReservedFracsecBit
+                    | BaseParseError::UnknownTimeQuality,
+                )
+                | ParseError::TypeRangeOverflow => ("FRACSEC", 10),
+                _ => ("SYNC", 0),
+            };
+            self.error(e)
+        })?;
+
+        if let Some(time_base) = time_base(&message.data, config) {
+            message.time.check_fracsec(time_base).map_err(|e| {
+                (self.field, self.offset) = ("FRACSEC", 10);
+                self.error(e)
+            })?;
+        }
+        Ok(message)
+    }
+
+    /// Marks the start of `field` at the current position
+    fn at(&mut self, field: &'static str) {
+        self.field = field;
+        self.offset = self.index;
+    }
+
+    fn read<T: Deserialize<'de>>(&mut self, field: &'static str) -> Result<T, ParseError> {
+        self.at(field);
+        T::deserialize(&mut *self)
+    }
+
+    fn error(&self, error: ParseError) -> DecodeError {
+        DecodeError {
+            error,
+            offset: self.offset,
+            field: self.field,
         }
     }
 
-    fn enque_checksum(&mut self, bytes: &[u8]) {
-        for &v in bytes {
-            let mut chk = self.checksum;
-            let temp = (chk >> 8) ^ (v as u16);
-            chk <<= 8;
-            let mut quick = temp ^ (temp >> 4);
-            chk ^= quick;
-            quick <<= 5;
-            chk ^= quick;
-            quick <<= 7;
-            chk ^= quick;
-            self.checksum = chk;
+    /// Checks that `len` bytes are left before CHK
+    fn advance(&mut self, len: usize) -> Result<(), ParseError> {
+        if len > self.remaining() {
+            return Err(ParseError::IllegalAccess);
         }
+        self.index += len;
+        Ok(())
+    }
+
+    fn take<const N: usize>(&mut self) -> Result<[u8; N], ParseError> {
+        self.advance(N)?;
+        let mut bytes = [0u8; N];
+        self.source.read(&mut bytes)?;
+        self.checksum.update(&bytes);
+        Ok(bytes)
+    }
+
+    fn take_slice(&mut self, len: usize) -> Result<&'de [u8], ParseError> {
+        self.advance(len)?;
+        let bytes = self.source.read_borrowed(len)?;
+        self.checksum.update(bytes);
+        Ok(bytes)
+    }
+
+    /// Like [`Self::take_slice`] for fields decoded into owned values, which
+    /// need no room in the scratch buffer of stream sources
+    #[cfg(feature = "alloc")]
+    fn take_vec(&mut self, len: usize) -> Result<Vec<u8>, ParseError> {
+        self.advance(len)?;
+        let mut bytes = alloc::vec![0u8; len];
+        self.source.read(&mut bytes)?;
+        self.checksum.update(&bytes);
+        Ok(bytes)
+    }
+
+    /// Bytes left before CHK
+    fn remaining(&self) -> usize {
+        self.end - self.index
+    }
+
+    /// All input has been consumed
+    #[cfg(feature = "alloc")]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.source.remaining() == Some(0)
     }
 
-    pub(crate) fn get_checksum(&self) -> u16 {
-        self.checksum
+    fn deserialize_frame(&mut self, config: ConfigRef) -> Result<Frame<'de>, ParseError> {
+        let sync = self.read::<u16>("SYNC")?;
+        if (sync & 0xFF00) != 0xAA00 {
+            return Err(ParseError::BaseParseError(
+                BaseParseError::IncorrectSyncWord,
+            ));
+        }
+        let framesize = self.read::<u16>("FRAMESIZE")?;
+        //Payload ends with the 2 byte CHK
+        self.end = (framesize as usize)
+            .checked_sub(2)
+            .filter(|&end| end >= self.index)
+            .ok_or(ParseError::BaseParseError(
+                BaseParseError::IncorrectFrameSize,
+            ))?;
+        if self
+            .source
+            .remaining()
+            .is_some_and(|remaining| remaining != self.remaining() + 2)
+        {
+            return Err(ParseError::BaseParseError(
+                BaseParseError::IncorrectFrameSize,
+            ));
+        }
+        let idcode = self.read::<u16>("IDCODE")?;
+        let soc = self.read::<u32>("SOC")?;
+        let fracsec = self.read::<u32>("FRACSEC")?;
+        //Bits 6-4: Frame type as listed in Table 2 of IEEE C37.118.2
+        let frame_type = FrameType::decode(((sync & 0x0070) >> 4) as u8)?;
+        let data = match frame_type {
+            FrameType::Data => self.deserialize_data_frame(config)?,
+            FrameType::Header => DataType::Header(self.deserialize_header()?),
+            #[cfg(feature = "alloc")]
+            FrameType::Cfg1 => DataType::Cfg1(self.deserialize_config()?),
+            #[cfg(feature = "alloc")]
+            FrameType::Cfg2 => DataType::Cfg2(self.deserialize_config()?),
+            FrameType::Cmd => DataType::Cmd(self.deserialize_command()?),
+            #[cfg(feature = "alloc")]
+            FrameType::Cfg3 => DataType::Cfg3(self.deserialize_cfg3_payload()?),
+            #[cfg(not(feature = "alloc"))]
+            FrameType::Cfg1 | FrameType::Cfg2 | FrameType::Cfg3 => {
+                return Err(ParseError::AllocRequired);
+            }
+        };
+        //Fixed layout payloads must end where FRAMESIZE says
+        if self.remaining() != 0 {
+            self.at("DATA");
+            return Err(match frame_type {
+                FrameType::Cfg1 | FrameType::Cfg2 | FrameType::Cfg3 => {
+                    ParseError::ConfigParseError(ConfigParseError::TrailingBytes)
+                }
+                _ => ParseError::BaseParseError(BaseParseError::IncorrectFrameSize),
+            });
+        }
+        Ok(Frame {
+            sync,
+            framesize,
+            idcode,
+            soc,
+            fracsec,
+            data,
+        })
+    }
+
+    #[cfg(feature = "alloc")]
+    fn deserialize_data_frame(&mut self, config: ConfigRef) -> Result<DataType<'de>, ParseError> {
+        let config = config.ok_or(ParseError::ConfigNeeded)?;
+        let pmus = config
+            .pmus
+            .iter()
+            .map(|pmu| self.deserialize_pmu_data(pmu))
+            .collect::<Result<_, _>>()?;
+        if self.remaining() != 0 {
+            //Configuration describes fewer bytes than the frame carries
+            self.at("DATA");
+            return Err(ParseError::ConfigMismatch);
+        }
+        Ok(DataType::Data(DataFrame { pmus }))
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    fn deserialize_data_frame(&mut self, _config: ConfigRef) -> Result<DataType<'de>, ParseError> {
+        Err(ParseError::AllocRequired)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn deserialize_pmu_data(&mut self, pmu: &PmuConfig) -> Result<PmuData, ParseError> {
+        let stat_raw = self.read::<u16>("STAT")?;
+        let phasors = (0..pmu.phnmr())
+            .map(|_| self.deserialize_phasor(&pmu.format))
+            .collect::<Result<_, _>>()?;
+        let freq = self.deserialize_scalar(&pmu.format.freq, "FREQ")?;
+        let dfreq = self.deserialize_scalar(&pmu.format.freq, "DFREQ")?;
+        let analog = (0..pmu.annmr())
+            .map(|_| self.deserialize_scalar(&pmu.format.analog, "ANALOG"))
+            .collect::<Result<_, _>>()?;
+        let digital = (0..pmu.dgnmr())
+            .map(|_| self.read::<u16>("DIGITAL"))
+            .collect::<Result<_, _>>()?;
+        Ok(PmuData {
+            stat_raw,
+            phasors,
+            freq,
+            dfreq,
+            analog,
+            digital,
+        })
+    }
+
+    #[cfg(feature = "alloc")]
+    fn deserialize_phasor(&mut self, format: &Format) -> Result<Phasor, ParseError> {
+        Ok(match (&format.phasor, &format.phasor_notation) {
+            (NumberFormat::Int, PhasorNotation::Rectangular) => Phasor::RectangularInt {
+                real: self.read::<i16>("PHASORS")?,
+                imaginary: self.read::<i16>("PHASORS")?,
+            },
+            (NumberFormat::Int, PhasorNotation::Polar) => Phasor::PolarInt {
+                magnitude: self.read::<u16>("PHASORS")?,
+                angle: self.read::<i16>("PHASORS")?,
+            },
+            (NumberFormat::Float, PhasorNotation::Rectangular) => Phasor::RectangularFloat {
+                real: self.read::<f32>("PHASORS")?,
+                imaginary: self.read::<f32>("PHASORS")?,
+            },
+            (NumberFormat::Float, PhasorNotation::Polar) => Phasor::PolarFloat {
+                magnitude: self.read::<f32>("PHASORS")?,
+                angle: self.read::<f32>("PHASORS")?,
+            },
+        })
+    }
+
+    #[cfg(feature = "alloc")]
+    fn deserialize_config(&mut self) -> Result<Config, ParseError> {
+        let time_base = self.read::<u32>("TIME_BASE")?;
+        //Bits 31-24: Reserved for flags
+        if (time_base & 0xFF00_0000) != 0 {
+            return Err(ParseError::ConfigParseError(
+                ConfigParseError::IncorrectReservedTimeBaseBits,
+            ));
+        }
+        let time_base = u24::new(time_base)?;
+        let num_pmu = self.read::<u16>("NUM_PMU")?;
+        let pmus = (0..num_pmu)
+            .map(|_| self.deserialize_pmu_config())
+            .collect::<Result<_, _>>()?;
+        let data_rate = self.read::<i16>("DATA_RATE")?;
+        Ok(Config {
+            time_base,
+            pmus,
+            data_rate,
+        })
+    }
+
+    #[cfg(feature = "alloc")]
+    fn deserialize_pmu_config(&mut self) -> Result<PmuConfig, ParseError> {
+        let station = self.deserialize_name("STN")?;
+        let idcode = self.read::<u16>("IDCODE")?;
+        let format = Format::decode(self.read::<u16>("FORMAT")?)?;
+        let phnmr = self.read::<u16>("PHNMR")?;
+        let annmr = self.read::<u16>("ANNMR")?;
+        let dgnmr = self.read::<u16>("DGNMR")?;
+
+        //CHNAM: Phasor names, then analog names, then 16 names per digital status word
+        let phasor_names = (0..phnmr)
+            .map(|_| self.deserialize_name("CHNAM"))
+            .collect::<Result<Vec<_>, _>>()?;
+        let analog_names = (0..annmr)
+            .map(|_| self.deserialize_name("CHNAM"))
+            .collect::<Result<Vec<_>, _>>()?;
+        let digital_names = (0..dgnmr)
+            .map(|_| {
+                let mut names: [Name; 16] = Default::default();
+                for name in names.iter_mut() {
+                    *name = self.deserialize_name("CHNAM")?;
+                }
+                Ok(names)
+            })
+            .collect::<Result<Vec<_>, ParseError>>()?;
+
+        let phasors = phasor_names
+            .into_iter()
+            .map(|name| {
+                let unit = PhasorUnit::decode(self.read::<u32>("PHUNIT")?)?;
+                Ok(PhasorChannel { name, unit })
+            })
+            .collect::<Result<_, ParseError>>()?;
+        let analogs = analog_names
+            .into_iter()
+            .map(|name| {
+                let unit = AnalogUnit::decode(self.read::<u32>("ANUNIT")?);
+                Ok(AnalogChannel { name, unit })
+            })
+            .collect::<Result<_, ParseError>>()?;
+        let digitals = digital_names
+            .into_iter()
+            .map(|names| {
+                let unit = DigitalUnit {
+                    normal: self.read::<u16>("DIGUNIT")?,
+                    valid: self.read::<u16>("DIGUNIT")?,
+                };
+                Ok(DigitalChannel { names, unit })
+            })
+            .collect::<Result<_, ParseError>>()?;
+
+        let fnom = NominalFrequency::decode(self.read::<u16>("FNOM")?)?;
+        let cfgcnt = self.read::<u16>("CFGCNT")?;
+        Ok(PmuConfig {
+            station,
+            idcode,
+            format,
+            phasors,
+            analogs,
+            digitals,
+            fnom,
+            cfgcnt,
+        })
+    }
+
+    #[cfg(feature = "alloc")]
+    fn deserialize_name(&mut self, field: &'static str) -> Result<Name, ParseError> {
+        self.at(field);
+        Ok(Name::decode(self.take::<16>()?))
+    }
+
+    fn deserialize_header(&mut self) -> Result<Header<'de>, ParseError> {
+        self.at("DATA");
+        Ok(Header::Borrowed(self.take_slice(self.remaining())?))
+    }
+
+    fn deserialize_command(&mut self) -> Result<Command<'de>, ParseError> {
+        let cmd = self.read::<u16>("CMD")?;
+        let extframe = match cmd {
+            //EXTFRAME: Remainder of the frame is user data
+            Command::EXTENDED_FRAME => {
+                self.at("EXTFRAME");
+                self.take_slice(self.remaining())?
+            }
+            _ => &[],
+        };
+        Ok(Command::decode(cmd, extframe))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn deserialize_cfg3_payload(&mut self) -> Result<Cfg3Payload, ParseError> {
+        let cont_idx = self.read::<u16>("CONT_IDX")?;
+        if cont_idx == 0 {
+            return Ok(Cfg3Payload::Config(self.deserialize_config3()?));
+        }
+        self.at("CONFIG");
+        let bytes = self.take_vec(self.remaining())?;
+        Ok(Cfg3Payload::Fragment(Cfg3Fragment { cont_idx, bytes }))
+    }
+
+    #[cfg(feature = "alloc")]
+    pub(crate) fn deserialize_config3(&mut self) -> Result<Config3, ParseError> {
+        let time_base = self.read::<u32>("TIME_BASE")?;
+        //Bits 31-24: Reserved for flags
+        if (time_base & 0xFF00_0000) != 0 {
+            return Err(ParseError::ConfigParseError(
+                ConfigParseError::IncorrectReservedTimeBaseBits,
+            ));
+        }
+        let time_base = u24::new(time_base)?;
+        let num_pmu = self.read::<u16>("NUM_PMU")?;
+        let pmus = (0..num_pmu)
+            .map(|_| self.deserialize_pmu_config3())
+            .collect::<Result<_, _>>()?;
+        let data_rate = self.read::<i16>("DATA_RATE")?;
+        Ok(Config3 {
+            time_base,
+            pmus,
+            data_rate,
+        })
+    }
+
+    #[cfg(feature = "alloc")]
+    fn deserialize_pmu_config3(&mut self) -> Result<PmuConfig3, ParseError> {
+        let station = self.deserialize_utf8_name("STN")?;
+        let idcode = self.read::<u16>("IDCODE")?;
+        self.at("G_PMU_ID");
+        let g_pmu_id = self.take::<16>()?;
+        let format = Format::decode(self.read::<u16>("FORMAT")?)?;
+        let phnmr = self.read::<u16>("PHNMR")?;
+        let annmr = self.read::<u16>("ANNMR")?;
+        let dgnmr = self.read::<u16>("DGNMR")?;
+
+        //CHNAM: Phasor names, then analog names, then 16 names per digital status word
+        let phasor_names = (0..phnmr)
+            .map(|_| self.deserialize_utf8_name("CHNAM"))
+            .collect::<Result<Vec<_>, _>>()?;
+        let analog_names = (0..annmr)
+            .map(|_| self.deserialize_utf8_name("CHNAM"))
+            .collect::<Result<Vec<_>, _>>()?;
+        let digital_names = (0..dgnmr)
+            .map(|_| {
+                let mut names: [String; 16] = Default::default();
+                for name in names.iter_mut() {
+                    *name = self.deserialize_utf8_name("CHNAM")?;
+                }
+                Ok(names)
+            })
+            .collect::<Result<Vec<_>, ParseError>>()?;
+
+        let phasors = phasor_names
+            .into_iter()
+            .map(|name| {
+                let phasor_type = self.read::<u32>("PHSCALE")?;
+                let magnitude = self.read::<f32>("PHSCALE")?;
+                let angle = self.read::<f32>("PHSCALE")?;
+                let scale = PhasorScale::decode(phasor_type, magnitude, angle)?;
+                Ok(PhasorChannel3 { name, scale })
+            })
+            .collect::<Result<_, ParseError>>()?;
+        let analogs = analog_names
+            .into_iter()
+            .map(|name| {
+                let scale = AnalogScale {
+                    magnitude: self.read::<f32>("ANSCALE")?,
+                    offset: self.read::<f32>("ANSCALE")?,
+                };
+                Ok(AnalogChannel3 { name, scale })
+            })
+            .collect::<Result<_, ParseError>>()?;
+        let digitals = digital_names
+            .into_iter()
+            .map(|names| {
+                let unit = DigitalUnit {
+                    normal: self.read::<u16>("DIGUNIT")?,
+                    valid: self.read::<u16>("DIGUNIT")?,
+                };
+                Ok(DigitalChannel3 { names, unit })
+            })
+            .collect::<Result<_, ParseError>>()?;
+
+        let latitude = self.read::<f32>("PMU_LAT")?;
+        let longitude = self.read::<f32>("PMU_LON")?;
+        let elevation = self.read::<f32>("PMU_ELEV")?;
+        let service_class = ServiceClass::decode(self.read::<u8>("SVC_CLASS")?)?;
+        let window = self.read::<i32>("WINDOW")?;
+        let group_delay = self.read::<i32>("GRP_DLY")?;
+        let fnom = NominalFrequency::decode(self.read::<u16>("FNOM")?)?;
+        let cfgcnt = self.read::<u16>("CFGCNT")?;
+        Ok(PmuConfig3 {
+            station,
+            idcode,
+            g_pmu_id,
+            format,
+            phasors,
+            analogs,
+            digitals,
+            latitude,
+            longitude,
+            elevation,
+            service_class,
+            window,
+            group_delay,
+            fnom,
+            cfgcnt,
+        })
+    }
+
+    #[cfg(feature = "alloc")]
+    fn deserialize_utf8_name(&mut self, field: &'static str) -> Result<String, ParseError> {
+        let len = self.read::<u8>(field)?;
+        let bytes = self.take_vec(len as usize)?;
+        String::from_utf8(bytes)
+            .map_err(|_| ParseError::ConfigParseError(ConfigParseError::InvalidName))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn deserialize_scalar(
+        &mut self,
+        format: &NumberFormat,
+        field: &'static str,
+    ) -> Result<Scalar, ParseError> {
+        Ok(match format {
+            NumberFormat::Int => Scalar::Int(self.read::<i16>(field)?),
+            NumberFormat::Float => Scalar::Float(self.read::<f32>(field)?),
+        })
     }
 }
 
-struct SynDeserializerSeqAccess<'a, 'de: 'a> {
-    deserializer: &'a mut SynDeserializer<'de>,
+/// Data frame borrowed from the input. Values are decoded when accessed, at
+/// the offsets given by the configuration, so no message is built per frame.
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+pub struct DataFrameRef<'a> {
+    header: FrameHeader,
+    payload: &'a [u8], //Between FRACSEC and CHK
+    config: &'a Config,
 }
 
-impl<'a, 'de> SynDeserializerSeqAccess<'a, 'de> {
-    fn new(deserializer: &'a mut SynDeserializer<'de>) -> SynDeserializerSeqAccess<'a, 'de> {
-        SynDeserializerSeqAccess::<'a, 'de> { deserializer }
+#[cfg(feature = "alloc")]
+impl<'a> DataFrameRef<'a> {
+    /// Checks the frame header, FRAMESIZE, CHK and that the payload has the
+    /// size `config` describes. Values are not decoded.
+    pub fn new(bytes: &'a [u8], config: &'a Config) -> Result<DataFrameRef<'a>, ParseError> {
+        let header = FrameHeader::peek(bytes)?;
+        if header.frame_type != FrameType::Data {
+            return Err(ParseError::BaseParseError(
+                BaseParseError::UnexpectedFrameType,
+            ));
+        }
+        if header.framesize as usize != bytes.len() {
+            return Err(ParseError::BaseParseError(
+                BaseParseError::IncorrectFrameSize,
+            ));
+        }
+        header.time.check_fracsec(&config.time_base)?;
+        if !checksum::verify_frame(bytes) {
+            return Err(ParseError::InvalidChecksum);
+        }
+        let payload = bytes
+            .get(14..bytes.len() - 2)
+            .ok_or(ParseError::IllegalAccess)?;
+        if payload.len() != config.pmus.iter().map(PmuConfig::data_size).sum::<usize>() {
+            return Err(ParseError::ConfigMismatch);
+        }
+        Ok(DataFrameRef {
+            header,
+            payload,
+            config,
+        })
+    }
+
+    pub fn header(&self) -> &FrameHeader {
+        &self.header
     }
+
+    /// NUM_PMU: Number of PMU blocks
+    pub fn num_pmu(&self) -> usize {
+        self.config.pmus.len()
+    }
+
+    /// Block of the PMU at `index` in the configuration
+    pub fn pmu(&self, index: usize) -> Option<PmuDataRef<'a>> {
+        let config = self.config.pmus.get(index)?;
+        let offset = self.config.pmus[..index]
+            .iter()
+            .map(PmuConfig::data_size)
+            .sum::<usize>();
+        Some(PmuDataRef {
+            bytes: &self.payload[offset..offset + config.data_size()],
+            config,
+        })
+    }
+
+    /// Blocks of all PMUs, in configuration order
+    pub fn pmus(&self) -> impl Iterator<Item = PmuDataRef<'a>> {
+        let mut payload = self.payload;
+        self.config.pmus.iter().map(move |config| {
+            let (bytes, rest) = payload.split_at(config.data_size());
+            payload = rest;
+            PmuDataRef { bytes, config }
+        })
+    }
+}
+
+/// Block of one PMU within a [`DataFrameRef`]
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, Copy)]
+pub struct PmuDataRef<'a> {
+    bytes: &'a [u8],
+    config: &'a PmuConfig,
 }
 
-impl<'de, 'a> SeqAccess<'de> for SynDeserializerSeqAccess<'a, 'de> {
+#[cfg(feature = "alloc")]
+impl<'a> PmuDataRef<'a> {
+    pub fn config(&self) -> &'a PmuConfig {
+        self.config
+    }
+
+    /// STAT: Bitmapped flags
+    pub fn stat(&self) -> Stat {
+        Stat::decode(self.stat_raw())
+    }
+
+    /// STAT as encoded
+    pub fn stat_raw(&self) -> u16 {
+        self.decode(0, |d| d.read::<u16>("STAT"))
+    }
+
+    /// Phasor estimate at `index`, `None` past PHNMR
+    pub fn phasor(&self, index: usize) -> Option<Phasor> {
+        let format = &self.config.format;
+        (index < self.config.phnmr()).then(|| {
+            self.decode(2 + index * 2 * format.phasor.size(), |d| {
+                d.deserialize_phasor(format)
+            })
+        })
+    }
+
+    /// FREQ: Frequency
+    pub fn freq(&self) -> Scalar {
+        self.decode(self.freq_offset(), |d| {
+            d.deserialize_scalar(&self.config.format.freq, "FREQ")
+        })
+    }
+
+    /// DFREQ: ROCOF
+    pub fn dfreq(&self) -> Scalar {
+        let offset = self.freq_offset() + self.config.format.freq.size();
+        self.decode(offset, |d| {
+            d.deserialize_scalar(&self.config.format.freq, "DFREQ")
+        })
+    }
+
+    /// FREQ in Hz
+    pub fn frequency(&self) -> f32 {
+        self.freq().frequency(&self.config.fnom)
+    }
+
+    /// DFREQ in Hz/s
+    pub fn rocof(&self) -> f32 {
+        self.dfreq().rocof()
+    }
+
+    /// Analog value at `index`, `None` past ANNMR
+    pub fn analog(&self, index: usize) -> Option<Scalar> {
+        let format = &self.config.format;
+        (index < self.config.annmr()).then(|| {
+            let offset = self.freq_offset() + 2 * format.freq.size() + index * format.analog.size();
+            self.decode(offset, |d| d.deserialize_scalar(&format.analog, "ANALOG"))
+        })
+    }
+
+    /// Digital status word at `index`, `None` past DGNMR
+    pub fn digital(&self, index: usize) -> Option<u16> {
+        (index < self.config.dgnmr()).then(|| {
+            self.decode(self.bytes.len() - 2 * (self.config.dgnmr() - index), |d| {
+                d.read::<u16>("DIGITAL")
+            })
+        })
+    }
+
+    fn freq_offset(&self) -> usize {
+        2 + self.config.phnmr() * 2 * self.config.format.phasor.size()
+    }
+
+    fn decode<T>(
+        &self,
+        offset: usize,
+        f: impl FnOnce(&mut SynDeserializer<'a>) -> Result<T, ParseError>,
+    ) -> T {
+        //Block size was checked against the configuration by DataFrameRef::new
+        f(&mut SynDeserializer::new(&self.bytes[offset..])).expect("value within PMU block")
+    }
+}
+
+struct SynDeserializerSeqAccess<'a, 'de: 'a, S> {
+    deserializer: &'a mut SynDeserializer<'de, S>,
+}
+
+impl<'a, 'de, S> SynDeserializerSeqAccess<'a, 'de, S> {
+    fn new(deserializer: &'a mut SynDeserializer<'de, S>) -> SynDeserializerSeqAccess<'a, 'de, S> {
+        SynDeserializerSeqAccess::<'a, 'de, S> { deserializer }
+    }
+}
+
+impl<'de, 'a, S: ByteSource<'de>> SeqAccess<'de> for SynDeserializerSeqAccess<'a, 'de, S> {
     type Error = ParseError;
 
     fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
@@ -79,157 +942,146 @@ impl<'de, 'a> SeqAccess<'de> for SynDeserializerSeqAccess<'a, 'de> {
     }
 }
 
-impl<'a, 'de> Deserializer<'de> for &'a mut SynDeserializer<'de> {
+impl<'de, S: ByteSource<'de>> Deserializer<'de> for &mut SynDeserializer<'de, S> {
     type Error = ParseError;
 
     fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!()
+        Err(ParseError::UnsupportedType)
     }
 
     fn deserialize_bool<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!()
+        Err(ParseError::UnsupportedType)
     }
 
     fn deserialize_i8<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!()
+        Err(ParseError::UnsupportedType)
     }
 
-    fn deserialize_i16<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+    fn deserialize_i16<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!()
+        let bytes = self.take::<2>()?;
+        visitor.visit_i16(i16::from_be_bytes(bytes))
     }
 
-    fn deserialize_i32<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+    fn deserialize_i32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!()
+        let bytes = self.take::<4>()?;
+        visitor.visit_i32(i32::from_be_bytes(bytes))
     }
 
     fn deserialize_i64<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!()
+        Err(ParseError::UnsupportedType)
     }
 
-    fn deserialize_u8<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+    fn deserialize_u8<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!()
+        let [value] = self.take::<1>()?;
+        visitor.visit_u8(value)
     }
 
     fn deserialize_u16<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        let index = self.index;
-        let bytes: [u8; 2] = self.bytes[index..index + 2]
-            .try_into()
-            .map_err(|_| ParseError::IllegalAccess)?;
-        self.enque_checksum(&bytes);
-        let value = u16::from_be_bytes(bytes);
-        self.index += 2;
-        if self.sync.is_none() {
-            self.sync = Some(value);
-        }
-        visitor.visit_u16(value)
+        let bytes = self.take::<2>()?;
+        visitor.visit_u16(u16::from_be_bytes(bytes))
     }
 
     fn deserialize_u32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        let index = self.index;
-        let bytes: [u8; 4] = self.bytes[index..index + 4]
-            .try_into()
-            .map_err(|_| ParseError::IllegalAccess)?;
-        self.enque_checksum(&bytes);
-        let value = u32::from_be_bytes(bytes);
-        self.index += 4;
-        visitor.visit_u32(value)
+        let bytes = self.take::<4>()?;
+        visitor.visit_u32(u32::from_be_bytes(bytes))
     }
 
     fn deserialize_u64<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!()
+        Err(ParseError::UnsupportedType)
     }
 
-    fn deserialize_f32<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+    fn deserialize_f32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!()
+        let bytes = self.take::<4>()?;
+        visitor.visit_f32(f32::from_be_bytes(bytes))
     }
 
     fn deserialize_f64<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!()
+        Err(ParseError::UnsupportedType)
     }
 
     fn deserialize_char<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!()
+        Err(ParseError::UnsupportedType)
     }
 
     fn deserialize_str<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!()
+        Err(ParseError::UnsupportedType)
     }
 
     fn deserialize_string<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!()
+        Err(ParseError::UnsupportedType)
     }
 
     fn deserialize_bytes<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!()
+        Err(ParseError::UnsupportedType)
     }
 
     fn deserialize_byte_buf<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!()
+        Err(ParseError::UnsupportedType)
     }
 
     fn deserialize_option<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!()
+        Err(ParseError::UnsupportedType)
     }
 
     fn deserialize_unit<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!()
+        Err(ParseError::UnsupportedType)
     }
 
     fn deserialize_unit_struct<V>(
@@ -240,7 +1092,7 @@ impl<'a, 'de> Deserializer<'de> for &'a mut SynDeserializer<'de> {
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!()
+        Err(ParseError::UnsupportedType)
     }
 
     fn deserialize_newtype_struct<V>(
@@ -251,7 +1103,7 @@ impl<'a, 'de> Deserializer<'de> for &'a mut SynDeserializer<'de> {
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!()
+        Err(ParseError::UnsupportedType)
     }
 
     fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
@@ -265,7 +1117,7 @@ impl<'a, 'de> Deserializer<'de> for &'a mut SynDeserializer<'de> {
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!()
+        Err(ParseError::UnsupportedType)
     }
 
     fn deserialize_tuple_struct<V>(
@@ -277,14 +1129,14 @@ impl<'a, 'de> Deserializer<'de> for &'a mut SynDeserializer<'de> {
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!()
+        Err(ParseError::UnsupportedType)
     }
 
     fn deserialize_map<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!()
+        Err(ParseError::UnsupportedType)
     }
 
     fn deserialize_struct<V>(
@@ -302,58 +1154,35 @@ impl<'a, 'de> Deserializer<'de> for &'a mut SynDeserializer<'de> {
 
     fn deserialize_enum<V>(
         self,
-        name: &'static str,
-        variants: &'static [&'static str],
-        visitor: V,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        _visitor: V,
     ) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        trace!("{:?}\n{:?}", name, variants);
-        if name == "DataType" {
-            if let Some(sync) = self.sync {
-                let frame_type = match (sync & 0x0070u16) >> 4 {
-                    0 => "Data",
-                    1 => "Header",
-                    2 => "Cfg1",
-                    3 => "Cfg2",
-                    4 => "Cmd",
-                    5 => "Cfg3",
-                    _ => {
-                        return Err(ParseError::BaseParseError(BaseParseError::UnknownFrameType));
-                    }
-                };
-                trace!("Before visit enum");
-                visitor.visit_enum(frame_type.into_deserializer())
-            } else {
-                Err(ParseError::BaseParseError(
-                    BaseParseError::IncorrectSyncWord,
-                ))
-            }
-        } else {
-            todo!()
-        }
+        Err(ParseError::UnsupportedType)
     }
 
     fn deserialize_identifier<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!()
+        Err(ParseError::UnsupportedType)
     }
 
     fn deserialize_ignored_any<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
     where
         V: serde::de::Visitor<'de>,
     {
-        todo!()
+        Err(ParseError::UnsupportedType)
     }
 }
 
 #[cfg(test)]
 mod deserializer_test {
 
-    use crate::DataType;
+    use crate::{checksum, DataType};
 
     use super::*;
     use core::marker::PhantomData;
@@ -400,7 +1229,7 @@ mod deserializer_test {
             );
         }
         let frame_checksum = u16::from_be_bytes([0x16, 0x8a]);
-        assert_eq!(u16_deserializer.checksum, frame_checksum);
+        assert_eq!(checksum::checksum(&frame_bytes), frame_checksum);
     }
 
     #[test]
@@ -444,88 +1273,73 @@ mod deserializer_test {
             );
         }
         let frame_checksum = u16::from_be_bytes([0x16, 0x8a]);
-        assert_eq!(u32_deserializer.checksum, frame_checksum);
+        assert_eq!(checksum::checksum(&frame_bytes), frame_checksum);
     }
 
     #[test]
-    fn deserialize_enum_checksum() {
-        struct ValueVisitor<'de> {
-            phantom: PhantomData<&'de ()>,
-        }
-        impl<'de> ValueVisitor<'de> {
-            pub fn new() -> ValueVisitor<'de> {
-                ValueVisitor {
-                    phantom: PhantomData,
-                }
-            }
-        }
-        impl<'de> serde::de::Visitor<'de> for ValueVisitor<'de> {
-            type Value = u16;
-            fn expecting(
-                &self,
-                _: &mut core::fmt::Formatter<'_>,
-            ) -> core::result::Result<(), core::fmt::Error> {
-                todo!()
-            }
-            fn visit_u16<E>(self, v: u16) -> Result<Self::Value, E>
-            where
-                E: serde::de::Error,
-            {
-                Ok(v)
-            }
-        }
+    fn deserialize_frame_type_checksum() {
+        let frame_bytes: [u8; 18] = [
+            0xaa, 0x41, 0x00, 0x12, 0x00, 0x3c, 0x48, 0x99, 0x90, 0x9a, 0x00, 0x90, 0x2e, 0x12,
+            0x00, 0x05, 0x16, 0x8a,
+        ];
+        let mut deserializer = SynDeserializer::new(&frame_bytes);
+        let frame = deserializer.deserialize_frame(None).unwrap();
+        assert_eq!(frame.data, DataType::Cmd(Command::SendCfg2));
+        assert_eq!(frame.idcode, 60);
 
-        struct TestVisitor<'de> {
-            phantom: PhantomData<&'de ()>,
-        }
-        impl<'de> TestVisitor<'de> {
-            pub fn new() -> TestVisitor<'de> {
-                TestVisitor {
-                    phantom: PhantomData,
-                }
-            }
-        }
-        impl<'de> serde::de::Visitor<'de> for TestVisitor<'de> {
-            type Value = DataType;
-            fn expecting(
-                &self,
-                _: &mut core::fmt::Formatter<'_>,
-            ) -> core::result::Result<(), core::fmt::Error> {
-                todo!()
-            }
-            fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
-            where
-                A: serde::de::EnumAccess<'de>,
-            {
-                trace!("Visit enum");
-                let (v, _variant_visitor) = data.variant()?;
-                trace!("Exiting Visit enum");
-                Ok(v)
-            }
-        }
+        //Checksum covers the base frame and CMD word
+        assert_eq!(deserializer.checksum.value(), 0x168a);
+    }
 
-        let frame_bytes: [u8; 16] = [
-            0xaa, 0x41, 0x00, 0x12, 0x00, 0x3c, 0x48, 0x99, 0x90, 0x9a, 0x00, 0x90, 0x2e, 0x12,
-            0x00, 0x05,
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn deserialize_data_frame_config_mismatch() {
+        use crate::config::*;
+        let frame_bytes: [u8; 24] = [
+            0xaa, 0x01, 0x00, 0x18, 0x00, 0x07, 0x44, 0x85, 0x36, 0x00, 0x00, 0x00, 0x41, 0xb1,
+            0x00, 0x00, 0x39, 0x2b, 0x00, 0x00, 0x09, 0xc4, 0xed, 0xe4,
         ];
+        let phasor = || PhasorChannel {
+            name: Name::new("VA").unwrap(),
+            unit: PhasorUnit {
+                kind: PhasorKind::Voltage,
+                factor: crate::u24::new(915527).unwrap(),
+            },
+        };
+        let mut config = Config {
+            time_base: crate::u24::new(1_000_000).unwrap(),
+            pmus: alloc::vec![PmuConfig {
+                station: Name::new("STATION A").unwrap(),
+                idcode: 7,
+                format: Format {
+                    freq: NumberFormat::Int,
+                    analog: NumberFormat::Int,
+                    phasor: NumberFormat::Int,
+                    phasor_notation: PhasorNotation::Rectangular,
+                },
+                phasors: alloc::vec![],
+                analogs: alloc::vec![],
+                digitals: alloc::vec![],
+                fnom: NominalFrequency::Hz60,
+                cfgcnt: 0,
+            }],
+            data_rate: 30,
+        };
+        let mut deserializer = SynDeserializer::new(&frame_bytes);
+        assert_eq!(
+            deserializer
+                .deserialize_frame(Some(&config))
+                .map(|f| f.data),
+            Err(ParseError::ConfigMismatch)
+        );
+
+        config.pmus[0].phasors = alloc::vec![phasor(), phasor()];
         let mut deserializer = SynDeserializer::new(&frame_bytes);
-        for _v in frame_bytes.chunks(2) {
-            let visitor = ValueVisitor::new();
-            deserializer.deserialize_u16(visitor).unwrap();
-        }
-        let visitor = TestVisitor::new();
-        //Function flow: Visitor, Visitor.Value=DataType
-        // Deserializer-> Deserialize enum<Visitor>
-        //    Visitor.Visit_Enum(Data: Into_Deserializer for &str (StrDeserializer), with EnumAccess Trait)->V::Value
-        //         data.Variant<T>() -> (T,Variant Access), T: DeserializeSeed
-        //         data.VariantSeed<T>(seed:PhantomData) -> (T,Variant Access)
         assert_eq!(
-            deserializer.deserialize_enum(
-                "DataType",
-                &["Header", "Cfg1", "Cfg2", "Cfg3", "Data", "Cmd"],
-                visitor
-            ),
-            Ok(DataType::Cmd)
+            deserializer
+                .deserialize_frame(Some(&config))
+                .map(|f| f.data),
+            Err(ParseError::IllegalAccess)
         );
     }
 }
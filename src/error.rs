@@ -1,27 +1,50 @@
+use core::fmt::{self, Display, Formatter, Write};
+
 #[derive(PartialEq, Debug)]
 pub enum ParseError {
     ConfigNeeded,      //Configuration needs to be provided
+    ConfigMismatch,    //Frame payload does not match the provided configuration
     TypeRangeOverflow, //Value overflow of allowed range for type
     BaseParseError(BaseParseError),
-    Custom,
+    ConfigParseError(ConfigParseError),
+    Custom(ErrorMessage),
     IllegalAccess,
     InvalidChecksum,
+    UnsupportedType, //Type has no representation in the frame format
+    SpaceExceeded,   //Borrowed field does not fit the buffer of the source
+    Io,              //Reading from the source failed
+    AllocRequired,   //Frame type is only decoded with the alloc feature
 }
 
 impl serde::de::Error for ParseError {
-    fn custom<T>(_msg: T) -> Self
+    fn custom<T>(msg: T) -> Self
     where
-        T: core::fmt::Display,
+        T: Display,
     {
-        todo!()
+        ParseError::Custom(ErrorMessage::new(msg))
     }
 }
 
 impl serde::de::StdError for ParseError {}
 
-impl core::fmt::Display for ParseError {
-    fn fmt(&self, _f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        todo!()
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::ConfigNeeded => f.write_str("configuration needed to decode data frame"),
+            ParseError::ConfigMismatch => {
+                f.write_str("frame payload does not match the configuration")
+            }
+            ParseError::TypeRangeOverflow => f.write_str("value out of range for its type"),
+            ParseError::BaseParseError(e) => e.fmt(f),
+            ParseError::ConfigParseError(e) => e.fmt(f),
+            ParseError::Custom(msg) => msg.fmt(f),
+            ParseError::IllegalAccess => f.write_str("frame ends before field"),
+            ParseError::InvalidChecksum => f.write_str("CHK does not match frame"),
+            ParseError::UnsupportedType => f.write_str("type not supported by frame format"),
+            ParseError::SpaceExceeded => f.write_str("field does not fit the source buffer"),
+            ParseError::Io => f.write_str("reading from the source failed"),
+            ParseError::AllocRequired => f.write_str("frame type requires the alloc feature"),
+        }
     }
 }
 
@@ -33,27 +56,232 @@ pub enum BaseParseError {
     IncorrectReservedFracsecBit,
     UnknownTimeQuality,
     UnknownFrameType,
+    IncorrectFrameSize,     // FRAMESIZE does not match the length of the frame
+    UnexpectedFrameType,    // Frame is not of the type being decoded
+    FracsecExceedsTimeBase, // FRACSEC is not below TIME_BASE
+}
+
+impl Display for BaseParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            BaseParseError::IncorrectSyncWord => "SYNC does not start with 0xAA",
+            BaseParseError::IncorrectReservedSyncBit => "SYNC reserved bit is set",
+            BaseParseError::UnknownFrameVersionNumber => "unknown frame version number",
+            BaseParseError::IncorrectReservedFracsecBit => "FRACSEC reserved bit is set",
+            BaseParseError::UnknownTimeQuality => "unknown time quality",
+            BaseParseError::UnknownFrameType => "unknown frame type",
+            BaseParseError::IncorrectFrameSize => "FRAMESIZE does not match frame length",
+            BaseParseError::UnexpectedFrameType => "frame is not of the expected type",
+            BaseParseError::FracsecExceedsTimeBase => "FRACSEC is not below TIME_BASE",
+        })
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub enum ConfigParseError {
+    IncorrectReservedTimeBaseBits,   // TIME_BASE bits 31-24 are not 0
+    IncorrectReservedFormatBits,     // FORMAT bits 15-4 are not 0
+    IncorrectReservedFnomBits,       // FNOM bits 15-1 are not 0
+    UnknownPhasorUnit,               // PHUNIT is neither voltage nor current
+    IncorrectReservedPhasorTypeBits, // PHSCALE bits 7-4 are not 0
+    UnknownServiceClass,             // SVC_CLASS is neither 'M' nor 'P'
+    InvalidName,                     // CFG-3 name is not valid UTF-8
+    UnexpectedFragment,              // CFG-3 fragment received out of order
+    FragmentsTooLarge,               // Reassembled CFG-3 exceeds Cfg3Assembler::MAX_SIZE
+    UnrepresentableScale,            // CFG-3 scale has no CFG-2 PHUNIT or ANUNIT equivalent
+    TrailingBytes,                   // Configuration has bytes after DATA_RATE
+}
+
+impl Display for ConfigParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ConfigParseError::IncorrectReservedTimeBaseBits => "TIME_BASE reserved bits are set",
+            ConfigParseError::IncorrectReservedFormatBits => "FORMAT reserved bits are set",
+            ConfigParseError::IncorrectReservedFnomBits => "FNOM reserved bits are set",
+            ConfigParseError::UnknownPhasorUnit => "PHUNIT is neither voltage nor current",
+            ConfigParseError::IncorrectReservedPhasorTypeBits => "PHSCALE reserved bits are set",
+            ConfigParseError::UnknownServiceClass => "SVC_CLASS is neither 'M' nor 'P'",
+            ConfigParseError::InvalidName => "name is not valid UTF-8",
+            ConfigParseError::UnexpectedFragment => "CFG-3 fragment out of order",
+            ConfigParseError::FragmentsTooLarge => "CFG-3 fragments exceed the reassembly limit",
+            ConfigParseError::UnrepresentableScale => {
+                "CFG-3 scale cannot be expressed as a CFG-2 unit"
+            }
+            ConfigParseError::TrailingBytes => "configuration has bytes after DATA_RATE",
+        })
+    }
+}
+
+/// Decode error with the position of the field it was raised at
+#[derive(PartialEq, Debug)]
+pub struct DecodeError {
+    pub error: ParseError,
+    pub offset: usize,       //Byte offset of the field within the frame
+    pub field: &'static str, //Field name as used by the standard
+}
+
+impl From<DecodeError> for ParseError {
+    fn from(e: DecodeError) -> Self {
+        e.error
+    }
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at byte {} ({})", self.error, self.offset, self.field)
+    }
 }
 
 #[derive(PartialEq, Debug)]
 pub enum SerializeError {
     SpaceExceeded,
-    Custom,
+    OutOfRange, //Value cannot be represented in the selected number format
+    Custom(ErrorMessage),
 }
 
 impl serde::ser::Error for SerializeError {
-    fn custom<T>(_msg: T) -> Self
+    fn custom<T>(msg: T) -> Self
     where
-        T: core::fmt::Display,
+        T: Display,
     {
-        todo!()
+        SerializeError::Custom(ErrorMessage::new(msg))
     }
 }
 
 impl serde::ser::StdError for SerializeError {}
 
-impl core::fmt::Display for SerializeError {
-    fn fmt(&self, _f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        todo!()
+impl Display for SerializeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SerializeError::SpaceExceeded => f.write_str("frame exceeds available space"),
+            SerializeError::OutOfRange => f.write_str("value out of range for number format"),
+            SerializeError::Custom(msg) => msg.fmt(f),
+        }
+    }
+}
+
+/// Error of the session state machines in [`crate::session`]
+#[derive(PartialEq, Debug)]
+pub enum SessionError {
+    UnexpectedIdcode, //Frame belongs to another data stream
+    UnexpectedFrame,  //Frame is not expected in the current state
+    ParseError(ParseError),
+    SerializeError(SerializeError),
+}
+
+impl From<ParseError> for SessionError {
+    fn from(e: ParseError) -> Self {
+        SessionError::ParseError(e)
+    }
+}
+
+impl From<SerializeError> for SessionError {
+    fn from(e: SerializeError) -> Self {
+        SessionError::SerializeError(e)
+    }
+}
+
+impl Display for SessionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SessionError::UnexpectedIdcode => f.write_str("frame belongs to another data stream"),
+            SessionError::UnexpectedFrame => f.write_str("frame not expected in session state"),
+            SessionError::ParseError(e) => e.fmt(f),
+            SessionError::SerializeError(e) => e.fmt(f),
+        }
+    }
+}
+
+/// Message of a custom error, truncated to a fixed capacity so it can be kept
+/// without allocating
+#[derive(PartialEq, Eq, Clone)]
+pub struct ErrorMessage {
+    bytes: [u8; ErrorMessage::CAPACITY],
+    len: usize,
+}
+
+impl ErrorMessage {
+    pub const CAPACITY: usize = 64;
+
+    pub fn new<T: Display>(msg: T) -> ErrorMessage {
+        let mut message = ErrorMessage {
+            bytes: [0; ErrorMessage::CAPACITY],
+            len: 0,
+        };
+        //Truncation is not an error
+        let _ = write!(message, "{}", msg);
+        message
+    }
+
+    pub fn as_str(&self) -> &str {
+        //Only whole characters are written
+        core::str::from_utf8(&self.bytes[..self.len]).unwrap_or_default()
+    }
+}
+
+impl Write for ErrorMessage {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let available = ErrorMessage::CAPACITY - self.len;
+        let mut end = s.len().min(available);
+        while !s.is_char_boundary(end) {
+            end -= 1;
+        }
+        self.bytes[self.len..self.len + end].copy_from_slice(&s.as_bytes()[..end]);
+        self.len += end;
+        if end < s.len() {
+            Err(fmt::Error)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Display for ErrorMessage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Debug for ErrorMessage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod error_test {
+    use super::*;
+    use alloc::string::ToString;
+    use serde::{de::Error as _, ser::Error as _};
+
+    #[test]
+    fn custom_message_is_kept() {
+        assert_eq!(
+            ParseError::custom("invalid length 3").to_string(),
+            "invalid length 3"
+        );
+        assert_eq!(
+            SerializeError::custom(format_args!("name exceeds {} bytes", 255)),
+            SerializeError::Custom(ErrorMessage::new("name exceeds 255 bytes"))
+        );
+    }
+
+    #[test]
+    fn custom_message_is_truncated() {
+        let message = ErrorMessage::new("Ä".repeat(40));
+        assert_eq!(message.as_str(), "Ä".repeat(32));
+    }
+
+    #[test]
+    fn decode_error_display() {
+        let error = DecodeError {
+            error: ParseError::ConfigParseError(ConfigParseError::UnknownPhasorUnit),
+            offset: 414,
+            field: "PHUNIT",
+        };
+        assert_eq!(
+            error.to_string(),
+            "PHUNIT is neither voltage nor current at byte 414 (PHUNIT)"
+        );
     }
 }
@@ -1,9 +1,19 @@
 #![no_std]
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+pub mod checksum;
 pub mod config;
+pub mod decoder;
 pub mod deserializer;
 pub mod error;
 pub mod message;
 pub mod serializer;
+#[cfg(feature = "alloc")]
+pub mod session;
+#[cfg(feature = "alloc")]
 pub use crate::config::Config;
 pub use crate::error::*;
 pub use crate::message::*;
@@ -0,0 +1,102 @@
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+
+/// CMD word of a command frame sent to a PMU or PDC
+#[derive(PartialEq, Debug, Clone)]
+pub enum Command<'a> {
+    TurnOffTransmission, //Turn off transmission of data frames
+    TurnOnTransmission,  //Turn on transmission of data frames
+    SendHeader,          //Send HDR frame
+    SendCfg1,            //Send CFG-1 frame
+    SendCfg2,            //Send CFG-2 frame
+    SendCfg3,            //Send CFG-3 frame
+    Extended(&'a [u8]),  //Extended frame, followed by user data
+    UserDesignated(u16), //0x0100-0x0FFF: Available for user designation
+    Reserved(u16),       //All undesignated codes
+}
+
+impl<'a> Command<'a> {
+    pub const EXTENDED_FRAME: u16 = 0x0008;
+
+    pub fn encode(&self) -> u16 {
+        match self {
+            Command::TurnOffTransmission => 0x0001,
+            Command::TurnOnTransmission => 0x0002,
+            Command::SendHeader => 0x0003,
+            Command::SendCfg1 => 0x0004,
+            Command::SendCfg2 => 0x0005,
+            Command::SendCfg3 => 0x0006,
+            Command::Extended(_) => Command::EXTENDED_FRAME,
+            Command::UserDesignated(cmd) | Command::Reserved(cmd) => *cmd,
+        }
+    }
+
+    /// Decodes the CMD word; `extframe` is only kept for extended frames.
+    pub fn decode(cmd: u16, extframe: &'a [u8]) -> Command<'a> {
+        match cmd {
+            0x0001 => Command::TurnOffTransmission,
+            0x0002 => Command::TurnOnTransmission,
+            0x0003 => Command::SendHeader,
+            0x0004 => Command::SendCfg1,
+            0x0005 => Command::SendCfg2,
+            0x0006 => Command::SendCfg3,
+            Command::EXTENDED_FRAME => Command::Extended(extframe),
+            0x0100..=0x0FFF => Command::UserDesignated(cmd),
+            _ => Command::Reserved(cmd),
+        }
+    }
+}
+
+impl<'a> Serialize for Command<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Command::Extended(extframe) => {
+                let mut state = serializer.serialize_struct("Command", 2)?;
+                state.serialize_field("cmd", &self.encode())?;
+                state.serialize_field("extframe", &ExtFrame(extframe))?;
+                state.end()
+            }
+            _ => {
+                let mut state = serializer.serialize_struct("Command", 1)?;
+                state.serialize_field("cmd", &self.encode())?;
+                state.end()
+            }
+        }
+    }
+}
+
+struct ExtFrame<'a>(&'a [u8]);
+
+impl<'a> Serialize for ExtFrame<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+#[cfg(test)]
+mod command_test {
+    use super::*;
+
+    #[test]
+    fn command_encoding() {
+        assert_eq!(Command::decode(0x0003, &[]), Command::SendHeader);
+        assert_eq!(Command::SendCfg3.encode(), 0x0006);
+        assert_eq!(
+            Command::decode(0x0008, &[0x12, 0x34]),
+            Command::Extended(&[0x12, 0x34])
+        );
+        for cmd in [0x0000, 0x0007, 0x00FF, 0x1000, 0xFFFF] {
+            assert_eq!(Command::decode(cmd, &[]), Command::Reserved(cmd));
+            assert_eq!(Command::Reserved(cmd).encode(), cmd);
+        }
+        for cmd in [0x0100, 0x0FFF] {
+            assert_eq!(Command::decode(cmd, &[]), Command::UserDesignated(cmd));
+            assert_eq!(Command::UserDesignated(cmd).encode(), cmd);
+        }
+    }
+}
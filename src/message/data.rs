@@ -0,0 +1,417 @@
+use crate::{
+    config::{
+        AnalogScale, AnalogUnit, Config, Format, NominalFrequency, NumberFormat, PhasorNotation,
+        PhasorScale, PhasorUnit, PmuConfig,
+    },
+    error::SerializeError,
+    ParseError, Stat,
+};
+use alloc::vec::Vec;
+use serde::Serialize;
+
+/// Data frame payload, one block per PMU listed in the configuration.
+#[derive(PartialEq, Debug, Clone, Serialize)]
+pub struct DataFrame {
+    pub pmus: Vec<PmuData>,
+}
+
+#[derive(PartialEq, Debug, Clone, Serialize)]
+pub struct PmuData {
+    pub stat_raw: u16,        //STAT as encoded
+    pub phasors: Vec<Phasor>, //PHNMR phasor estimates
+    pub freq: Scalar,         //Frequency
+    pub dfreq: Scalar,        //ROCOF
+    pub analog: Vec<Scalar>,  //ANNMR analog values
+    pub digital: Vec<u16>,    //DGNMR digital status words
+}
+
+impl DataFrame {
+    /// Pairs each PMU block with its configuration block, in NUM_PMU order.
+    /// Fails with [`ParseError::ConfigMismatch`] if the blocks do not match
+    /// the configuration in number, channel counts or formats.
+    pub fn blocks<'a>(
+        &'a self,
+        config: &'a Config,
+    ) -> Result<impl Iterator<Item = (&'a PmuConfig, &'a PmuData)>, ParseError> {
+        if self.pmus.len() != config.pmus.len()
+            || !config
+                .pmus
+                .iter()
+                .zip(&self.pmus)
+                .all(|(pmu, data)| data.matches(pmu))
+        {
+            return Err(ParseError::ConfigMismatch);
+        }
+        Ok(config.pmus.iter().zip(&self.pmus))
+    }
+
+    /// Block of the PMU with `idcode`
+    pub fn pmu<'a>(&'a self, config: &Config, idcode: u16) -> Option<&'a PmuData> {
+        let index = config.pmus.iter().position(|pmu| pmu.idcode == idcode)?;
+        self.pmus.get(index)
+    }
+}
+
+impl PmuData {
+    /// Channel counts and number formats agree with the configuration block
+    pub fn matches(&self, config: &PmuConfig) -> bool {
+        let Format {
+            freq,
+            analog,
+            phasor,
+            phasor_notation,
+        } = &config.format;
+        self.phasors.len() == config.phnmr()
+            && self.analog.len() == config.annmr()
+            && self.digital.len() == config.dgnmr()
+            && self.freq.format() == *freq
+            && self.dfreq.format() == *freq
+            && self.analog.iter().all(|value| value.format() == *analog)
+            && self
+                .phasors
+                .iter()
+                .all(|value| value.format() == (phasor.clone(), phasor_notation.clone()))
+    }
+
+    /// STAT: Bitmapped flags
+    pub fn stat(&self) -> Stat {
+        Stat::decode(self.stat_raw)
+    }
+
+    /// FREQ in Hz
+    pub fn frequency(&self, config: &PmuConfig) -> f32 {
+        self.freq.frequency(&config.fnom)
+    }
+
+    /// DFREQ in Hz/s
+    pub fn rocof(&self) -> f32 {
+        self.dfreq.rocof()
+    }
+}
+
+/// Phasor estimate as encoded on the wire, selected by the FORMAT field
+#[derive(PartialEq, Debug, Clone, Serialize)]
+pub enum Phasor {
+    RectangularInt { real: i16, imaginary: i16 },
+    PolarInt { magnitude: u16, angle: i16 }, //Angle in radians x 10^4
+    RectangularFloat { real: f32, imaginary: f32 },
+    PolarFloat { magnitude: f32, angle: f32 }, //Angle in radians
+}
+
+impl Phasor {
+    fn format(&self) -> (NumberFormat, PhasorNotation) {
+        match self {
+            Phasor::RectangularInt { .. } => (NumberFormat::Int, PhasorNotation::Rectangular),
+            Phasor::PolarInt { .. } => (NumberFormat::Int, PhasorNotation::Polar),
+            Phasor::RectangularFloat { .. } => (NumberFormat::Float, PhasorNotation::Rectangular),
+            Phasor::PolarFloat { .. } => (NumberFormat::Float, PhasorNotation::Polar),
+        }
+    }
+
+    /// Real part, in the units of the frame
+    pub fn real(&self) -> f32 {
+        match *self {
+            Phasor::RectangularInt { real, .. } => real as f32,
+            Phasor::RectangularFloat { real, .. } => real,
+            _ => self.magnitude() * libm::cosf(self.angle()),
+        }
+    }
+
+    /// Imaginary part, in the units of the frame
+    pub fn imaginary(&self) -> f32 {
+        match *self {
+            Phasor::RectangularInt { imaginary, .. } => imaginary as f32,
+            Phasor::RectangularFloat { imaginary, .. } => imaginary,
+            _ => self.magnitude() * libm::sinf(self.angle()),
+        }
+    }
+
+    /// Magnitude, in the units of the frame
+    pub fn magnitude(&self) -> f32 {
+        match *self {
+            Phasor::PolarInt { magnitude, .. } => magnitude as f32,
+            Phasor::PolarFloat { magnitude, .. } => magnitude,
+            _ => libm::hypotf(self.real(), self.imaginary()),
+        }
+    }
+
+    /// Angle in radians
+    pub fn angle(&self) -> f32 {
+        match *self {
+            Phasor::PolarInt { angle, .. } => angle as f32 / 10_000.0,
+            Phasor::PolarFloat { angle, .. } => angle,
+            _ => libm::atan2f(self.imaginary(), self.real()),
+        }
+    }
+
+    /// Converts to volts or amps with PHUNIT of a CFG-1/CFG-2 frame. Floating
+    /// point phasors already are in these units and are returned unchanged.
+    pub fn scale(&self, unit: &PhasorUnit) -> Phasor {
+        self.to_float(unit.factor.encode() as f32 * 1e-5, 0.0)
+    }
+
+    /// Converts to volts or amps with PHSCALE of a CFG-3 frame. The magnitude
+    /// factor applies to integer phasors, the angle adjustment to all.
+    pub fn scale_cfg3(&self, scale: &PhasorScale) -> Phasor {
+        self.to_float(scale.magnitude, scale.angle)
+    }
+
+    fn to_float(&self, factor: f32, angle: f32) -> Phasor {
+        let factor = match self {
+            Phasor::RectangularInt { .. } | Phasor::PolarInt { .. } => factor,
+            Phasor::RectangularFloat { .. } | Phasor::PolarFloat { .. } => 1.0,
+        };
+        match self {
+            Phasor::RectangularInt { .. } | Phasor::RectangularFloat { .. } => {
+                let (sin, cos) = libm::sincosf(angle);
+                let (real, imaginary) = (self.real() * factor, self.imaginary() * factor);
+                Phasor::RectangularFloat {
+                    real: real * cos - imaginary * sin,
+                    imaginary: real * sin + imaginary * cos,
+                }
+            }
+            Phasor::PolarInt { .. } | Phasor::PolarFloat { .. } => Phasor::PolarFloat {
+                magnitude: self.magnitude() * factor,
+                angle: self.angle() + angle,
+            },
+        }
+    }
+}
+
+/// FREQ, DFREQ or ANALOG value as encoded on the wire, selected by the FORMAT field
+#[derive(PartialEq, Debug, Clone, Serialize)]
+pub enum Scalar {
+    Int(i16),
+    Float(f32),
+}
+
+impl Scalar {
+    fn format(&self) -> NumberFormat {
+        match self {
+            Scalar::Int(_) => NumberFormat::Int,
+            Scalar::Float(_) => NumberFormat::Float,
+        }
+    }
+
+    /// Value in the units of the frame
+    pub fn value(&self) -> f32 {
+        match *self {
+            Scalar::Int(value) => value as f32,
+            Scalar::Float(value) => value,
+        }
+    }
+
+    /// Frequency in Hz of a FREQ value. Integer values are the deviation
+    /// from FNOM in mHz.
+    pub fn frequency(&self, fnom: &NominalFrequency) -> f32 {
+        match *self {
+            Scalar::Int(deviation) => fnom.hz() as f32 + deviation as f32 / 1000.0,
+            Scalar::Float(frequency) => frequency,
+        }
+    }
+
+    /// ROCOF in Hz/s of a DFREQ value. Integer values are ROCOF x 100.
+    pub fn rocof(&self) -> f32 {
+        match *self {
+            Scalar::Int(rocof) => rocof as f32 / 100.0,
+            Scalar::Float(rocof) => rocof,
+        }
+    }
+
+    /// Encodes a frequency in Hz as FREQ
+    pub fn from_frequency(
+        frequency: f32,
+        fnom: &NominalFrequency,
+        format: &NumberFormat,
+    ) -> Result<Scalar, SerializeError> {
+        match format {
+            NumberFormat::Int => Scalar::int((frequency - fnom.hz() as f32) * 1000.0),
+            NumberFormat::Float => Scalar::float(frequency),
+        }
+    }
+
+    /// Encodes a ROCOF in Hz/s as DFREQ
+    pub fn from_rocof(rocof: f32, format: &NumberFormat) -> Result<Scalar, SerializeError> {
+        match format {
+            NumberFormat::Int => Scalar::int(rocof * 100.0),
+            NumberFormat::Float => Scalar::float(rocof),
+        }
+    }
+
+    fn int(value: f32) -> Result<Scalar, SerializeError> {
+        let value = libm::roundf(value);
+        //Also rejects NaN
+        if (i16::MIN as f32..=i16::MAX as f32).contains(&value) {
+            Ok(Scalar::Int(value as i16))
+        } else {
+            Err(SerializeError::OutOfRange)
+        }
+    }
+
+    fn float(value: f32) -> Result<Scalar, SerializeError> {
+        if value.is_finite() {
+            Ok(Scalar::Float(value))
+        } else {
+            Err(SerializeError::OutOfRange)
+        }
+    }
+
+    /// Converts an ANALOG value with ANUNIT of a CFG-1/CFG-2 frame. Floating
+    /// point values are returned unchanged.
+    pub fn scale_analog(&self, unit: &AnalogUnit) -> f32 {
+        match *self {
+            Scalar::Int(value) => value as f32 * unit.factor.value() as f32,
+            Scalar::Float(value) => value,
+        }
+    }
+
+    /// Converts an ANALOG value with the scale M and offset B of a CFG-3
+    /// frame, as M * value + B
+    pub fn scale_analog_cfg3(&self, scale: &AnalogScale) -> f32 {
+        scale.magnitude * self.value() + scale.offset
+    }
+}
+
+#[cfg(test)]
+mod data_test {
+    use super::*;
+    use crate::{
+        config::{AnalogKind, PhasorComponent, PhasorKind},
+        i24, u24,
+    };
+
+    fn assert_close(left: f32, right: f32) {
+        assert!(
+            (left - right).abs() <= right.abs() * 1e-5 + 1e-5,
+            "{left} != {right}"
+        );
+    }
+
+    #[test]
+    fn phasor_accessors() {
+        let rectangular = Phasor::RectangularInt {
+            real: 3,
+            imaginary: -4,
+        };
+        assert_close(rectangular.magnitude(), 5.0);
+        assert_close(rectangular.angle(), libm::atan2f(-4.0, 3.0));
+
+        let polar = Phasor::PolarInt {
+            magnitude: 2,
+            angle: 12_500,
+        };
+        assert_close(polar.angle(), 1.25);
+        assert_close(polar.real(), 2.0 * libm::cosf(1.25));
+        assert_close(polar.imaginary(), 2.0 * libm::sinf(1.25));
+    }
+
+    #[test]
+    fn phasor_unit_conversion() {
+        let unit = PhasorUnit {
+            kind: PhasorKind::Voltage,
+            factor: u24::new(915527).unwrap(),
+        };
+        let phasor = Phasor::RectangularInt {
+            real: 14635,
+            imaginary: 0,
+        }
+        .scale(&unit);
+        assert_close(phasor.real(), 133_987.4);
+        assert_close(phasor.imaginary(), 0.0);
+
+        let polar = Phasor::PolarFloat {
+            magnitude: 120.5,
+            angle: -1.5,
+        };
+        assert_eq!(polar.scale(&unit), polar);
+    }
+
+    #[test]
+    fn phasor_scale_conversion() {
+        let scale = PhasorScale {
+            modification: 0,
+            user: 0,
+            kind: PhasorKind::Current,
+            component: PhasorComponent::PhaseA,
+            magnitude: 0.5,
+            angle: 0.25,
+        };
+        let phasor = Phasor::PolarInt {
+            magnitude: 100,
+            angle: 10_000,
+        }
+        .scale_cfg3(&scale);
+        assert_close(phasor.magnitude(), 50.0);
+        assert_close(phasor.angle(), 1.25);
+
+        //Angle adjustment rotates rectangular phasors
+        let phasor = Phasor::RectangularFloat {
+            real: 10.0,
+            imaginary: 0.0,
+        }
+        .scale_cfg3(&scale);
+        assert_close(phasor.magnitude(), 10.0);
+        assert_close(phasor.angle(), 0.25);
+    }
+
+    #[test]
+    fn frequency_conversion() {
+        assert_eq!(Scalar::Int(2500).frequency(&NominalFrequency::Hz60), 62.5);
+        assert_eq!(Scalar::Int(-500).frequency(&NominalFrequency::Hz50), 49.5);
+        assert_eq!(
+            Scalar::Float(59.75).frequency(&NominalFrequency::Hz50),
+            59.75
+        );
+        assert_eq!(Scalar::Int(-25).rocof(), -0.25);
+
+        assert_eq!(
+            Scalar::from_frequency(62.5, &NominalFrequency::Hz60, &NumberFormat::Int),
+            Ok(Scalar::Int(2500))
+        );
+        assert_eq!(
+            Scalar::from_frequency(49.5, &NominalFrequency::Hz50, &NumberFormat::Float),
+            Ok(Scalar::Float(49.5))
+        );
+        assert_eq!(
+            Scalar::from_rocof(-0.25, &NumberFormat::Int),
+            Ok(Scalar::Int(-25))
+        );
+    }
+
+    #[test]
+    fn frequency_out_of_range() {
+        //Deviation beyond +/-32.767 Hz does not fit an integer FREQ
+        assert_eq!(
+            Scalar::from_frequency(93.0, &NominalFrequency::Hz60, &NumberFormat::Int),
+            Err(SerializeError::OutOfRange)
+        );
+        assert_eq!(
+            Scalar::from_rocof(400.0, &NumberFormat::Int),
+            Err(SerializeError::OutOfRange)
+        );
+        assert_eq!(
+            Scalar::from_rocof(f32::NAN, &NumberFormat::Int),
+            Err(SerializeError::OutOfRange)
+        );
+        assert_eq!(
+            Scalar::from_frequency(f32::INFINITY, &NominalFrequency::Hz50, &NumberFormat::Float),
+            Err(SerializeError::OutOfRange)
+        );
+    }
+
+    #[test]
+    fn analog_conversion() {
+        let unit = AnalogUnit {
+            kind: AnalogKind::Rms,
+            factor: i24::new(-3).unwrap(),
+        };
+        assert_eq!(Scalar::Int(100).scale_analog(&unit), -300.0);
+        assert_eq!(Scalar::Float(1.5).scale_analog(&unit), 1.5);
+
+        let scale = AnalogScale {
+            magnitude: 2.0,
+            offset: 1.0,
+        };
+        assert_eq!(Scalar::Int(3).scale_analog_cfg3(&scale), 7.0);
+    }
+}
@@ -0,0 +1,90 @@
+#[cfg(feature = "alloc")]
+use alloc::{string::String, vec::Vec};
+use serde::{Serialize, Serializer};
+
+/// Human readable description of the data source carried by a header frame.
+/// Decoded headers borrow from the input frame.
+#[derive(Debug, Clone)]
+pub enum Header<'a> {
+    Borrowed(&'a [u8]),
+    #[cfg(feature = "alloc")]
+    Owned(Vec<u8>),
+}
+
+impl<'a> Header<'a> {
+    pub fn new(text: &'a str) -> Header<'a> {
+        Header::Borrowed(text.as_bytes())
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            Header::Borrowed(bytes) => bytes,
+            #[cfg(feature = "alloc")]
+            Header::Owned(bytes) => bytes,
+        }
+    }
+
+    /// Header text, `None` if it is not ASCII
+    pub fn as_str(&self) -> Option<&str> {
+        let bytes = self.as_bytes();
+        if bytes.is_ascii() {
+            core::str::from_utf8(bytes).ok()
+        } else {
+            None
+        }
+    }
+
+    /// Copies a borrowed header so it can outlive the input frame
+    #[cfg(feature = "alloc")]
+    pub fn into_owned(self) -> Header<'static> {
+        match self {
+            Header::Borrowed(bytes) => Header::Owned(bytes.to_vec()),
+            Header::Owned(bytes) => Header::Owned(bytes),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<String> for Header<'static> {
+    fn from(text: String) -> Self {
+        Header::Owned(text.into_bytes())
+    }
+}
+
+impl<'a, 'b> PartialEq<Header<'b>> for Header<'a> {
+    fn eq(&self, other: &Header<'b>) -> bool {
+        self.as_bytes() == other.as_bytes()
+    }
+}
+
+impl<'a> Serialize for Header<'a> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(self.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod header_test {
+    use super::*;
+
+    #[test]
+    fn header_text() {
+        let header = Header::new("Station A, 230kV bus");
+        assert_eq!(header.as_str(), Some("Station A, 230kV bus"));
+        assert_eq!(Header::Borrowed(&[0x53, 0xC4]).as_str(), None);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn owned_header_equals_borrowed() {
+        let header = Header::new("Station A");
+        assert_eq!(
+            header.clone().into_owned(),
+            Header::from(String::from("Station A"))
+        );
+        assert_eq!(header.into_owned(), Header::Borrowed(b"Station A"));
+    }
+}
@@ -0,0 +1,305 @@
+use super::{u24, Time};
+use crate::error::{ParseError, SerializeError};
+use core::time::Duration;
+
+/// UTC midnight following a leap second and TAI - UTC from then on
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct LeapSecond {
+    pub soc: u32,        //SOC of the midnight after the leap second
+    pub tai_offset: i32, //TAI - UTC in seconds
+}
+
+const fn leap(soc: u32, tai_offset: i32) -> LeapSecond {
+    LeapSecond { soc, tai_offset }
+}
+
+/// Leap seconds announced by the IERS up to the 2017-01-01 insertion
+pub const LEAP_SECONDS: &[LeapSecond] = &[
+    leap(63_072_000, 10), //1972-01-01, start of the table
+    leap(78_796_800, 11),
+    leap(94_694_400, 12),
+    leap(126_230_400, 13),
+    leap(157_766_400, 14),
+    leap(189_302_400, 15),
+    leap(220_924_800, 16),
+    leap(252_460_800, 17),
+    leap(283_996_800, 18),
+    leap(315_532_800, 19),
+    leap(362_793_600, 20),
+    leap(394_329_600, 21),
+    leap(425_865_600, 22),
+    leap(489_024_000, 23),
+    leap(567_993_600, 24),
+    leap(631_152_000, 25),
+    leap(662_688_000, 26),
+    leap(709_948_800, 27),
+    leap(741_484_800, 28),
+    leap(773_020_800, 29),
+    leap(820_454_400, 30),
+    leap(867_715_200, 31),
+    leap(915_148_800, 32),
+    leap(1_136_073_600, 33),
+    leap(1_230_768_000, 34),
+    leap(1_341_100_800, 35),
+    leap(1_435_708_800, 36),
+    leap(1_483_228_800, 37),
+];
+
+/// SOC of the GPS epoch, 1980-01-06
+const GPS_EPOCH: u64 = 315_964_800;
+/// TAI - GPS in seconds
+const GPS_TAI_OFFSET: u64 = 19;
+/// Leap second flags stay set for 24 hours after the leap second
+const OCCURRED_HOLD: u32 = 86_400;
+/// Leap second pending is set up to 60 s before the leap second
+const PENDING_LEAD: u32 = 60;
+
+impl Time {
+    /// Time stamp of an inserted leap second, 23:59:60. SOC does not count
+    /// leap seconds, so it shares the SOC of the following midnight, which
+    /// no longer has the leap second pending.
+    pub fn is_leap_second(&self) -> bool {
+        self.leap_second_pending && !self.leap_second_direction && self.soc.is_multiple_of(86_400)
+    }
+}
+
+/// Leap second table used to relate SOC, which follows UTC, to TAI and GPS
+/// time. [`LeapSeconds::default`] uses the embedded [`LEAP_SECONDS`]; newer
+/// announcements are handled by passing an extended table.
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub struct LeapSeconds<'a> {
+    table: &'a [LeapSecond],
+}
+
+impl Default for LeapSeconds<'static> {
+    fn default() -> Self {
+        LeapSeconds::new(LEAP_SECONDS)
+    }
+}
+
+impl<'a> LeapSeconds<'a> {
+    /// `table` must be sorted by SOC. Times before its first entry use the
+    /// offset of that entry.
+    pub const fn new(table: &'a [LeapSecond]) -> LeapSeconds<'a> {
+        LeapSeconds { table }
+    }
+
+    /// TAI - UTC in seconds at `time`
+    pub fn tai_offset(&self, time: &Time) -> i32 {
+        let offset = self.offset_at(time.soc);
+        if time.is_leap_second() {
+            //Inserted second precedes the offset change at midnight
+            offset - 1
+        } else {
+            offset
+        }
+    }
+
+    /// Time since 1970-01-01 00:00:00 TAI
+    pub fn to_tai(&self, time: &Time, time_base: &u24) -> Result<Duration, ParseError> {
+        let utc = time.to_duration(time_base)?;
+        let offset = self.tai_offset(time);
+        let tai = if offset >= 0 {
+            utc.checked_add(Duration::from_secs(offset as u64))
+        } else {
+            utc.checked_sub(Duration::from_secs(offset.unsigned_abs() as u64))
+        };
+        tai.ok_or(ParseError::TypeRangeOverflow)
+    }
+
+    /// Time since the GPS epoch, 1980-01-06 00:00:00 UTC
+    pub fn to_gps(&self, time: &Time, time_base: &u24) -> Result<Duration, ParseError> {
+        self.to_tai(time, time_base)?
+            .checked_sub(Duration::from_secs(GPS_EPOCH + GPS_TAI_OFFSET))
+            .ok_or(ParseError::TypeRangeOverflow)
+    }
+
+    /// Time stamp of `tai` after 1970-01-01 00:00:00 TAI, leap second flags
+    /// set as by [`LeapSeconds::set_flags`]. Inserted leap seconds are
+    /// flagged as [`Time::is_leap_second`].
+    pub fn from_tai(&self, tai: Duration, time_base: &u24) -> Result<Time, SerializeError> {
+        let tai_secs = tai.as_secs() as i64;
+        let subsec = Duration::from_nanos(tai.subsec_nanos() as u64);
+        //TAI at the midnight following each leap second
+        let midnight = |leap: &LeapSecond| leap.soc as i64 + leap.tai_offset as i64;
+        let index = self
+            .table
+            .partition_point(|leap| midnight(leap) <= tai_secs);
+        let offset = self
+            .table
+            .get(index.saturating_sub(1))
+            .map_or(0, |leap| leap.tai_offset);
+        //Inserted second is one second before the new offset applies
+        let inserted = self.table.get(index).filter(|leap| {
+            index > 0 && midnight(leap) == tai_secs + 1 && leap.tai_offset == offset + 1
+        });
+        let (soc, leap_second) = match inserted {
+            Some(leap) => (leap.soc as i64, true),
+            None => (tai_secs - offset as i64, false),
+        };
+        let soc = u64::try_from(soc).map_err(|_| SerializeError::OutOfRange)?;
+        let mut time = Time::from_duration(Duration::from_secs(soc) + subsec, time_base)?;
+        if leap_second {
+            time.leap_second_pending = true;
+        } else {
+            self.set_flags(&mut time);
+        }
+        Ok(time)
+    }
+
+    /// Time stamp of `gps` after the GPS epoch, see [`LeapSeconds::from_tai`]
+    pub fn from_gps(&self, gps: Duration, time_base: &u24) -> Result<Time, SerializeError> {
+        let tai = gps
+            .checked_add(Duration::from_secs(GPS_EPOCH + GPS_TAI_OFFSET))
+            .ok_or(SerializeError::OutOfRange)?;
+        self.from_tai(tai, time_base)
+    }
+
+    /// Sets the leap second bits of FRACSEC as a PMU would: pending in the
+    /// 60 s before a leap second, occurred for 24 h after it, and direction
+    /// of the leap second pending or last occurred. `time` must not be an
+    /// inserted leap second itself.
+    pub fn set_flags(&self, time: &mut Time) {
+        let index = self.table.partition_point(|leap| leap.soc <= time.soc);
+        let deleted = |i: usize| {
+            i.checked_sub(1)
+                .is_some_and(|previous| self.table[i].tai_offset < self.table[previous].tai_offset)
+        };
+        time.leap_second_pending = false;
+        time.leap_second_occured = false;
+        time.leap_second_direction = false;
+        if let Some(next) = self.table.get(index).filter(|_| index > 0) {
+            if next.soc - time.soc <= PENDING_LEAD {
+                time.leap_second_pending = true;
+                time.leap_second_direction = deleted(index);
+                return;
+            }
+        }
+        if let Some(last) = index.checked_sub(1).filter(|&i| i > 0) {
+            if time.soc - self.table[last].soc < OCCURRED_HOLD {
+                time.leap_second_occured = true;
+                time.leap_second_direction = deleted(last);
+            }
+        }
+    }
+
+    fn offset_at(&self, soc: u32) -> i32 {
+        let index = self.table.partition_point(|leap| leap.soc <= soc);
+        self.table
+            .get(index.saturating_sub(1))
+            .map_or(0, |leap| leap.tai_offset)
+    }
+}
+
+#[cfg(test)]
+mod leap_test {
+    use super::*;
+
+    //2017-01-01 00:00:00 UTC, after the last leap second in the table
+    const MIDNIGHT: u32 = 1_483_228_800;
+
+    fn time(soc: u32) -> Time {
+        Time::from_duration(Duration::from_secs(soc as u64), &time_base()).unwrap()
+    }
+
+    fn time_base() -> u24 {
+        u24::new(1_000_000).unwrap()
+    }
+
+    #[test]
+    fn tai_across_inserted_leap_second() {
+        let leap_seconds = LeapSeconds::default();
+        let mut before = time(MIDNIGHT - 1);
+        leap_seconds.set_flags(&mut before);
+        let leap = leap_seconds
+            .from_tai(Duration::from_secs(MIDNIGHT as u64 + 36), &time_base())
+            .unwrap();
+        let mut after = time(MIDNIGHT);
+        leap_seconds.set_flags(&mut after);
+
+        assert!(before.leap_second_pending && !before.is_leap_second());
+        assert!(leap.is_leap_second());
+        assert_eq!(leap.soc, MIDNIGHT);
+        assert!(after.leap_second_occured && !after.leap_second_pending);
+
+        let tai = [&before, &leap, &after]
+            .map(|time| leap_seconds.to_tai(time, &time_base()).unwrap().as_secs());
+        assert_eq!(
+            tai,
+            [
+                MIDNIGHT as u64 + 35,
+                MIDNIGHT as u64 + 36,
+                MIDNIGHT as u64 + 37
+            ]
+        );
+        for (time, tai) in [before, leap, after].iter().zip(tai) {
+            assert_eq!(
+                leap_seconds.from_tai(Duration::from_secs(tai), &time_base()),
+                Ok(time.clone())
+            );
+        }
+    }
+
+    #[test]
+    fn gps_time() {
+        let leap_seconds = LeapSeconds::default();
+        assert_eq!(
+            leap_seconds.to_gps(&time(GPS_EPOCH as u32), &time_base()),
+            Ok(Duration::ZERO)
+        );
+        //GPS is 18 s ahead of UTC since 2017
+        let time = time(MIDNIGHT + 86_400 * 365);
+        let gps = leap_seconds.to_gps(&time, &time_base()).unwrap();
+        assert_eq!(gps.as_secs(), (time.soc as u64 - GPS_EPOCH) + 18);
+        assert_eq!(leap_seconds.from_gps(gps, &time_base()), Ok(time));
+    }
+
+    #[test]
+    fn simulator_flags() {
+        let leap_seconds = LeapSeconds::default();
+        let flags = |soc: u32| {
+            let mut time = time(soc);
+            leap_seconds.set_flags(&mut time);
+            (
+                time.leap_second_pending,
+                time.leap_second_occured,
+                time.leap_second_direction,
+            )
+        };
+        assert_eq!(flags(MIDNIGHT - 61), (false, false, false));
+        assert_eq!(flags(MIDNIGHT - 60), (true, false, false));
+        assert_eq!(flags(MIDNIGHT + 86_399), (false, true, false));
+        assert_eq!(flags(MIDNIGHT + 86_400), (false, false, false));
+    }
+
+    #[test]
+    fn announced_leap_second_deletion() {
+        let mut table = [LEAP_SECONDS[LEAP_SECONDS.len() - 1]; 2];
+        table[1] = LeapSecond {
+            soc: MIDNIGHT + 86_400,
+            tai_offset: 36,
+        };
+        let leap_seconds = LeapSeconds::new(&table);
+
+        let mut time = time(MIDNIGHT + 86_400 - 30);
+        leap_seconds.set_flags(&mut time);
+        assert!(time.leap_second_pending && time.leap_second_direction);
+        assert!(!time.is_leap_second());
+
+        //23:59:59 is skipped, 23:59:58 is followed by midnight in TAI
+        let tai = leap_seconds
+            .to_tai(
+                &Time {
+                    soc: MIDNIGHT + 86_398,
+                    ..time.clone()
+                },
+                &time_base(),
+            )
+            .unwrap();
+        let midnight = leap_seconds
+            .from_tai(tai + Duration::from_secs(1), &time_base())
+            .unwrap();
+        assert_eq!(midnight.soc, MIDNIGHT + 86_400);
+        assert!(midnight.leap_second_occured && midnight.leap_second_direction);
+    }
+}
@@ -0,0 +1,203 @@
+/// STAT: Bitmapped flags leading each PMU block of a data frame, Table 7 of
+/// IEEE C37.118.2
+#[derive(PartialEq, Debug, Clone)]
+pub struct Stat {
+    pub data_error: DataError,         //Bits 15-14
+    pub pmu_sync: bool,                //Bit 13: In sync with a UTC traceable time source
+    pub data_sorting: DataSorting,     //Bit 12
+    pub trigger_detected: bool,        //Bit 11
+    pub config_change: bool,           //Bit 10: Configuration will change in 1 min
+    pub data_modified: bool,           //Bit 09: Modified by post processing
+    pub time_quality: PmuTimeQuality,  //Bits 08-06
+    pub unlocked_time: UnlockedTime,   //Bits 05-04
+    pub trigger_reason: TriggerReason, //Bits 03-00
+}
+
+impl Stat {
+    pub fn encode(&self) -> u16 {
+        let mut stat = (match self.data_error {
+            DataError::Good => 0b00,
+            DataError::PmuError => 0b01,
+            DataError::TestMode => 0b10,
+            DataError::Invalid => 0b11,
+        }) << 14;
+        if !self.pmu_sync {
+            stat |= 1 << 13;
+        }
+        if self.data_sorting == DataSorting::ByArrival {
+            stat |= 1 << 12;
+        }
+        if self.trigger_detected {
+            stat |= 1 << 11;
+        }
+        if self.config_change {
+            stat |= 1 << 10;
+        }
+        if self.data_modified {
+            stat |= 1 << 9;
+        }
+        stat |= (match self.time_quality {
+            PmuTimeQuality::NotUsed => 0b000,
+            PmuTimeQuality::Max100ns => 0b001,
+            PmuTimeQuality::Max1us => 0b010,
+            PmuTimeQuality::Max10us => 0b011,
+            PmuTimeQuality::Max100us => 0b100,
+            PmuTimeQuality::Max1ms => 0b101,
+            PmuTimeQuality::Max10ms => 0b110,
+            PmuTimeQuality::Unknown => 0b111,
+        }) << 6;
+        stat |= (match self.unlocked_time {
+            UnlockedTime::Under10s => 0b00,
+            UnlockedTime::Under100s => 0b01,
+            UnlockedTime::Under1000s => 0b10,
+            UnlockedTime::Over1000s => 0b11,
+        }) << 4;
+        stat | match self.trigger_reason {
+            TriggerReason::Manual => 0b0000,
+            TriggerReason::MagnitudeLow => 0b0001,
+            TriggerReason::MagnitudeHigh => 0b0010,
+            TriggerReason::PhaseAngleDiff => 0b0011,
+            TriggerReason::FrequencyHighOrLow => 0b0100,
+            TriggerReason::DfDtHigh => 0b0101,
+            TriggerReason::Reserved => 0b0110,
+            TriggerReason::Digital => 0b0111,
+            TriggerReason::User(code) => (code & 0x07) as u16 | 0b1000,
+        }
+    }
+
+    /// Every bit pattern is defined, so decoding cannot fail
+    pub fn decode(stat: u16) -> Stat {
+        Stat {
+            data_error: match stat >> 14 {
+                0b00 => DataError::Good,
+                0b01 => DataError::PmuError,
+                0b10 => DataError::TestMode,
+                _ => DataError::Invalid,
+            },
+            pmu_sync: (stat & (1 << 13)) == 0,
+            data_sorting: match (stat >> 12) & 1 {
+                0 => DataSorting::ByTime,
+                _ => DataSorting::ByArrival,
+            },
+            trigger_detected: (stat & (1 << 11)) != 0,
+            config_change: (stat & (1 << 10)) != 0,
+            data_modified: (stat & (1 << 9)) != 0,
+            time_quality: match (stat >> 6) & 0b111 {
+                0b000 => PmuTimeQuality::NotUsed,
+                0b001 => PmuTimeQuality::Max100ns,
+                0b010 => PmuTimeQuality::Max1us,
+                0b011 => PmuTimeQuality::Max10us,
+                0b100 => PmuTimeQuality::Max100us,
+                0b101 => PmuTimeQuality::Max1ms,
+                0b110 => PmuTimeQuality::Max10ms,
+                _ => PmuTimeQuality::Unknown,
+            },
+            unlocked_time: match (stat >> 4) & 0b11 {
+                0b00 => UnlockedTime::Under10s,
+                0b01 => UnlockedTime::Under100s,
+                0b10 => UnlockedTime::Under1000s,
+                _ => UnlockedTime::Over1000s,
+            },
+            trigger_reason: match stat & 0b1111 {
+                0b0000 => TriggerReason::Manual,
+                0b0001 => TriggerReason::MagnitudeLow,
+                0b0010 => TriggerReason::MagnitudeHigh,
+                0b0011 => TriggerReason::PhaseAngleDiff,
+                0b0100 => TriggerReason::FrequencyHighOrLow,
+                0b0101 => TriggerReason::DfDtHigh,
+                0b0110 => TriggerReason::Reserved,
+                0b0111 => TriggerReason::Digital,
+                code => TriggerReason::User((code & 0x07) as u8),
+            },
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum DataError {
+    Good,     //Good measurement data, no errors
+    PmuError, //PMU error, no information about data
+    TestMode, //PMU in test mode or absent data tags inserted, do not use values
+    Invalid,  //PMU error, do not use values
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum DataSorting {
+    ByTime,    //Sorted by time stamp
+    ByArrival, //Sorted by arrival
+}
+
+/// Maximum time error of the PMU, Table 8 of IEEE C37.118.2. Not used by
+/// IEEE C37.118-2005 frames.
+#[derive(PartialEq, Debug, Clone)]
+pub enum PmuTimeQuality {
+    NotUsed,  //Indicates code from previous version of profile
+    Max100ns, //Estimated maximum time error < 100ns
+    Max1us,   //Estimated maximum time error < 1us
+    Max10us,  //Estimated maximum time error < 10us
+    Max100us, //Estimated maximum time error < 100us
+    Max1ms,   //Estimated maximum time error < 1ms
+    Max10ms,  //Estimated maximum time error < 10ms
+    Unknown,  //Estimated maximum time error > 10ms or time error unknown
+}
+
+/// Time since the PMU lost synchronization
+#[derive(PartialEq, Debug, Clone)]
+pub enum UnlockedTime {
+    Under10s,   //Sync locked or unlocked < 10s (best quality)
+    Under100s,  //10s <= unlocked time < 100s
+    Under1000s, //100s < unlocked time <= 1000s
+    Over1000s,  //Unlocked time > 1000s
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum TriggerReason {
+    Manual,
+    MagnitudeLow,
+    MagnitudeHigh,
+    PhaseAngleDiff,
+    FrequencyHighOrLow,
+    DfDtHigh,
+    Reserved,
+    Digital,
+    User(u8), //Codes 1000-1111, holds the low 3 bits
+}
+
+#[cfg(test)]
+mod stat_test {
+    use super::*;
+
+    #[test]
+    fn stat_encoding() {
+        let stat = Stat::decode(0x0000);
+        assert_eq!(stat.data_error, DataError::Good);
+        assert!(stat.pmu_sync);
+        assert_eq!(stat.time_quality, PmuTimeQuality::NotUsed);
+        assert_eq!(stat.trigger_reason, TriggerReason::Manual);
+
+        //Test mode, not in sync, trigger on df/dt, time error < 1us, unlocked 10-100s
+        let stat = Stat::decode(0xA895);
+        assert_eq!(
+            stat,
+            Stat {
+                data_error: DataError::TestMode,
+                pmu_sync: false,
+                data_sorting: DataSorting::ByTime,
+                trigger_detected: true,
+                config_change: false,
+                data_modified: false,
+                time_quality: PmuTimeQuality::Max1us,
+                unlocked_time: UnlockedTime::Under100s,
+                trigger_reason: TriggerReason::DfDtHigh,
+            }
+        );
+        assert_eq!(Stat::decode(0x000D).trigger_reason, TriggerReason::User(5));
+    }
+
+    #[test]
+    fn stat_round_trip() {
+        for stat in 0..=u16::MAX {
+            assert_eq!(Stat::decode(stat).encode(), stat);
+        }
+    }
+}
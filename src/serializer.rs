@@ -1,15 +1,126 @@
-use crate::{error::SerializeError, Message};
+use crate::{checksum, error::SerializeError, Message};
 use log::trace;
 use serde::{Serialize, Serializer};
 
+/// Output of [`SynSerializer`].
+///
+/// FRAMESIZE and CHK are written into the frame once its payload is
+/// complete, through [`ByteContainer::written_mut`].
 pub trait ByteContainer {
     fn enque(&mut self, v: u8) -> Result<(), SerializeError>;
     fn get(&self) -> &[u8];
+
+    /// Number of bytes written so far
+    fn position(&self) -> usize {
+        self.get().len()
+    }
+
+    /// Bytes written so far, open to modification
+    fn written_mut(&mut self) -> &mut [u8];
+
+    /// Overwrites two already written bytes at `at` with `value`, big-endian.
+    /// Used to fill in FRAMESIZE once the frame is complete.
+    fn patch_u16(&mut self, at: usize, value: u16) -> Result<(), SerializeError> {
+        self.written_mut()
+            .get_mut(at..at + 2)
+            .ok_or(SerializeError::SpaceExceeded)?
+            .copy_from_slice(&value.to_be_bytes());
+        Ok(())
+    }
+}
+
+/// Fixed buffer filled from the start, either borrowed (`&mut [u8]`) or
+/// owned (`[u8; N]`). Fails with [`SerializeError::SpaceExceeded`] when full.
+pub struct Cursor<T> {
+    buffer: T,
+    len: usize,
+}
+
+impl<T: AsRef<[u8]> + AsMut<[u8]>> Cursor<T> {
+    pub fn new(buffer: T) -> Cursor<T> {
+        Cursor { buffer, len: 0 }
+    }
+
+    /// Number of bytes written
+    pub fn position(&self) -> usize {
+        self.len
+    }
+
+    pub fn into_inner(self) -> T {
+        self.buffer
+    }
+}
+
+impl<T: AsRef<[u8]> + AsMut<[u8]>> ByteContainer for Cursor<T> {
+    fn enque(&mut self, v: u8) -> Result<(), SerializeError> {
+        let byte = self
+            .buffer
+            .as_mut()
+            .get_mut(self.len)
+            .ok_or(SerializeError::SpaceExceeded)?;
+        *byte = v;
+        self.len += 1;
+        Ok(())
+    }
+
+    fn get(&self) -> &[u8] {
+        &self.buffer.as_ref()[..self.len]
+    }
+
+    fn written_mut(&mut self) -> &mut [u8] {
+        &mut self.buffer.as_mut()[..self.len]
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl ByteContainer for alloc::vec::Vec<u8> {
+    fn enque(&mut self, v: u8) -> Result<(), SerializeError> {
+        self.push(v);
+        Ok(())
+    }
+
+    fn get(&self) -> &[u8] {
+        self
+    }
+
+    fn written_mut(&mut self) -> &mut [u8] {
+        self
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<const N: usize> ByteContainer for heapless::Vec<u8, N> {
+    fn enque(&mut self, v: u8) -> Result<(), SerializeError> {
+        self.push(v).map_err(|_| SerializeError::SpaceExceeded)
+    }
+
+    fn get(&self) -> &[u8] {
+        self
+    }
+
+    fn written_mut(&mut self) -> &mut [u8] {
+        self
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl ByteContainer for bytes::BytesMut {
+    fn enque(&mut self, v: u8) -> Result<(), SerializeError> {
+        bytes::BufMut::put_u8(self, v);
+        Ok(())
+    }
+
+    fn get(&self) -> &[u8] {
+        self
+    }
+
+    fn written_mut(&mut self) -> &mut [u8] {
+        self
+    }
 }
 
 pub struct SynSerializer<B: ByteContainer> {
     bytes: B,
-    checksum: u16,
 }
 
 impl<B> SynSerializer<B>
@@ -17,39 +128,29 @@ where
     B: ByteContainer,
 {
     pub fn new(bytes: B) -> SynSerializer<B> {
-        SynSerializer {
-            bytes,
-            //Initial checksum
-            checksum: 0xFF_FF,
-        }
+        SynSerializer { bytes }
     }
 
     fn enque(&mut self, v: u8) -> Result<(), SerializeError> {
-        self.bytes.enque(v)?;
-        let mut chk = self.checksum;
-        let temp = (chk >> 8) ^ (v as u16);
-        chk <<= 8;
-        let mut quick = temp ^ (temp >> 4);
-        chk ^= quick;
-        quick <<= 5;
-        chk ^= quick;
-        quick <<= 7;
-        chk ^= quick;
-        self.checksum = chk;
-        Ok(())
+        self.bytes.enque(v)
     }
 
+    /// Appends `m` as a complete frame. FRAMESIZE and CHK are written as
+    /// placeholders and filled in by [`checksum::finalize_frame`] once the
+    /// payload is written.
     pub fn to_bytes(mut self, m: &Message) -> Result<B, SerializeError> {
+        let start = self.bytes.position();
         m.serialize(&mut self)?;
-
-        //Add checksum
-        self.serialize_end()?;
-
+        self.serialize_u16(0)?;
+        checksum::finalize_frame(&mut self.bytes.written_mut()[start..])?;
         Ok(self.bytes)
     }
 
-    fn serialize_end(&mut self) -> Result<(), SerializeError> {
-        self.serialize_u16(self.checksum)
+    /// Encodes `value` without framing or checksum
+    #[cfg(feature = "alloc")]
+    pub(crate) fn encode<T: Serialize>(mut self, value: &T) -> Result<B, SerializeError> {
+        value.serialize(&mut self)?;
+        Ok(self.bytes)
     }
 }
 
@@ -90,12 +191,16 @@ where
         todo!()
     }
 
-    fn serialize_i16(self, _v: i16) -> Result<Self::Ok, Self::Error> {
-        todo!()
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
+        v.to_be_bytes()
+            .into_iter()
+            .try_fold((), |_, v| self.enque(v))
     }
 
-    fn serialize_i32(self, _v: i32) -> Result<Self::Ok, Self::Error> {
-        todo!()
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
+        v.to_be_bytes()
+            .into_iter()
+            .try_fold((), |_, v| self.enque(v))
     }
 
     fn serialize_i64(self, _v: i64) -> Result<Self::Ok, Self::Error> {
@@ -141,9 +246,8 @@ where
     }
 
     fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
-        v.chars()
-            .into_iter()
-            .try_fold((), |_, v| self.enque(v as u8))
+        //UTF-8 encoded, ASCII names are written unchanged
+        v.bytes().try_fold((), |_, v| self.enque(v))
     }
 
     fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
@@ -154,9 +258,9 @@ where
         Ok(())
     }
 
-    fn serialize_some<T: ?Sized>(self, value: &T) -> Result<Self::Ok, Self::Error>
+    fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
     where
-        T: serde::Serialize,
+        T: ?Sized + serde::Serialize,
     {
         value.serialize(self)
     }
@@ -178,36 +282,38 @@ where
         Ok(())
     }
 
-    fn serialize_newtype_struct<T: ?Sized>(
+    fn serialize_newtype_struct<T>(
         self,
         _name: &'static str,
         _value: &T,
     ) -> Result<Self::Ok, Self::Error>
     where
-        T: serde::Serialize,
+        T: ?Sized + serde::Serialize,
     {
         todo!()
     }
 
-    fn serialize_newtype_variant<T: ?Sized>(
+    fn serialize_newtype_variant<T>(
         self,
         _name: &'static str,
         _variant_index: u32,
         _variant: &'static str,
-        _value: &T,
+        value: &T,
     ) -> Result<Self::Ok, Self::Error>
     where
-        T: serde::Serialize,
+        T: ?Sized + serde::Serialize,
     {
-        todo!()
+        //Variant is implied by the frame type or FORMAT field, only the value is encoded
+        value.serialize(self)
     }
 
     fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
-        todo!()
+        //Element count is carried by configuration fields, not encoded with the sequence
+        Ok(self)
     }
 
     fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
-        todo!()
+        Ok(self)
     }
 
     fn serialize_tuple_struct(
@@ -244,12 +350,13 @@ where
 
     fn serialize_struct_variant(
         self,
-        _name: &'static str,
+        name: &'static str,
         _variant_index: u32,
-        _variant: &'static str,
-        _len: usize,
+        variant: &'static str,
+        len: usize,
     ) -> Result<Self::SerializeStructVariant, Self::Error> {
-        todo!()
+        trace!("{}::{},{}", name, variant, len);
+        Ok(self)
     }
 }
 
@@ -261,15 +368,15 @@ where
 
     type Error = SerializeError;
 
-    fn serialize_element<T: ?Sized>(&mut self, _value: &T) -> Result<(), Self::Error>
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
     where
-        T: serde::Serialize,
+        T: ?Sized + serde::Serialize,
     {
-        todo!()
+        value.serialize(&mut **self)
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        todo!()
+        Ok(())
     }
 }
 
@@ -281,16 +388,16 @@ where
 
     type Error = SerializeError;
 
-    fn serialize_key<T: ?Sized>(&mut self, _key: &T) -> Result<(), Self::Error>
+    fn serialize_key<T>(&mut self, _key: &T) -> Result<(), Self::Error>
     where
-        T: serde::Serialize,
+        T: ?Sized + serde::Serialize,
     {
         todo!()
     }
 
-    fn serialize_value<T: ?Sized>(&mut self, _value: &T) -> Result<(), Self::Error>
+    fn serialize_value<T>(&mut self, _value: &T) -> Result<(), Self::Error>
     where
-        T: serde::Serialize,
+        T: ?Sized + serde::Serialize,
     {
         todo!()
     }
@@ -308,13 +415,9 @@ where
 
     type Error = SerializeError;
 
-    fn serialize_field<T: ?Sized>(
-        &mut self,
-        key: &'static str,
-        value: &T,
-    ) -> Result<(), Self::Error>
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
     where
-        T: serde::Serialize,
+        T: ?Sized + serde::Serialize,
     {
         trace!("{}", key);
         value.serialize(&mut **self)?;
@@ -334,19 +437,16 @@ where
 
     type Error = SerializeError;
 
-    fn serialize_field<T: ?Sized>(
-        &mut self,
-        _key: &'static str,
-        _value: &T,
-    ) -> Result<(), Self::Error>
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
     where
-        T: serde::Serialize,
+        T: ?Sized + serde::Serialize,
     {
-        todo!()
+        trace!("{}", key);
+        value.serialize(&mut **self)
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        todo!()
+        Ok(())
     }
 }
 
@@ -358,15 +458,15 @@ where
 
     type Error = SerializeError;
 
-    fn serialize_element<T: ?Sized>(&mut self, _value: &T) -> Result<(), Self::Error>
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
     where
-        T: serde::Serialize,
+        T: ?Sized + serde::Serialize,
     {
-        todo!()
+        value.serialize(&mut **self)
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        todo!()
+        Ok(())
     }
 }
 
@@ -378,9 +478,9 @@ where
 
     type Error = SerializeError;
 
-    fn serialize_field<T: ?Sized>(&mut self, _value: &T) -> Result<(), Self::Error>
+    fn serialize_field<T>(&mut self, _value: &T) -> Result<(), Self::Error>
     where
-        T: serde::Serialize,
+        T: ?Sized + serde::Serialize,
     {
         todo!()
     }
@@ -398,9 +498,9 @@ where
 
     type Error = SerializeError;
 
-    fn serialize_field<T: ?Sized>(&mut self, _value: &T) -> Result<(), Self::Error>
+    fn serialize_field<T>(&mut self, _value: &T) -> Result<(), Self::Error>
     where
-        T: serde::Serialize,
+        T: ?Sized + serde::Serialize,
     {
         todo!()
     }
@@ -415,36 +515,11 @@ mod serializer_test {
 
     use super::*;
     use test_log::test;
-    struct FixedContainer {
-        bytes: [u8; 65535],
-        index: usize,
-    }
-
-    impl FixedContainer {
-        pub fn new() -> FixedContainer {
-            FixedContainer {
-                bytes: [0; 65535],
-                index: 0,
-            }
-        }
-    }
-    impl ByteContainer for FixedContainer {
-        fn enque(&mut self, v: u8) -> Result<(), SerializeError> {
-            self.bytes[self.index] = v;
-            self.index += 1;
-            Ok(())
-        }
-
-        fn get(&self) -> &[u8] {
-            &self.bytes[0..self.index]
-        }
-    }
-
     #[test]
     fn serialize_u8_char_bytes_check_checksum() {
-        let u8_container = FixedContainer::new();
-        let char_container = FixedContainer::new();
-        let bytes_container = FixedContainer::new();
+        let u8_container = Cursor::new([0u8; 64]);
+        let char_container = Cursor::new([0u8; 64]);
+        let bytes_container = Cursor::new([0u8; 64]);
         let mut u8_serializer = SynSerializer::new(u8_container);
         let mut char_serializer = SynSerializer::new(char_container);
         let mut bytes_serializer = SynSerializer::new(bytes_container);
@@ -455,19 +530,28 @@ mod serializer_test {
         let frame_checksum = u16::from_be_bytes([0x16, 0x8a]);
         let result = bytes_serializer.serialize_bytes(&frame_bytes);
         assert_eq!(result, Ok(()));
-        assert_eq!(bytes_serializer.checksum, frame_checksum);
+        assert_eq!(
+            checksum::checksum(bytes_serializer.bytes.get()),
+            frame_checksum
+        );
         for value in frame_bytes {
             let result = u8_serializer.serialize_u8(value);
             assert_eq!(result, Ok(()));
             let result = char_serializer.serialize_char(value as char);
             assert_eq!(result, Ok(()));
         }
-        assert_eq!(u8_serializer.checksum, frame_checksum);
-        assert_eq!(char_serializer.checksum, frame_checksum);
+        assert_eq!(
+            checksum::checksum(u8_serializer.bytes.get()),
+            frame_checksum
+        );
+        assert_eq!(
+            checksum::checksum(char_serializer.bytes.get()),
+            frame_checksum
+        );
     }
     #[test]
     fn serialize_u16_check_checksum() {
-        let container = FixedContainer::new();
+        let container = Cursor::new([0u8; 64]);
         let mut serializer = SynSerializer::new(container);
         let frame_bytes: [u8; 16] = [
             0xaa, 0x41, 0x00, 0x12, 0x00, 0x3c, 0x48, 0x99, 0x90, 0x9a, 0x00, 0x90, 0x2e, 0x12,
@@ -487,11 +571,11 @@ mod serializer_test {
             let result = serializer.serialize_u16(value);
             assert_eq!(result, Ok(()));
         }
-        assert_eq!(serializer.checksum, frame_checksum);
+        assert_eq!(checksum::checksum(serializer.bytes.get()), frame_checksum);
     }
     #[test]
     fn serialize_u32_check_checksum() {
-        let container = FixedContainer::new();
+        let container = Cursor::new([0u8; 64]);
         let mut serializer = SynSerializer::new(container);
         let frame_bytes: [u8; 16] = [
             0xaa, 0x41, 0x00, 0x12, 0x00, 0x3c, 0x48, 0x99, 0x90, 0x9a, 0x00, 0x90, 0x2e, 0x12,
@@ -513,6 +597,57 @@ mod serializer_test {
             let result = serializer.serialize_u32(value);
             assert_eq!(result, Ok(()));
         }
-        assert_eq!(serializer.checksum, frame_checksum);
+        assert_eq!(checksum::checksum(serializer.bytes.get()), frame_checksum);
+    }
+
+    #[test]
+    fn cursor_space_exceeded() {
+        let mut buffer = [0u8; 3];
+        let mut cursor = Cursor::new(&mut buffer[..]);
+        for v in [0xAA, 0x41, 0x00] {
+            assert_eq!(cursor.enque(v), Ok(()));
+        }
+        assert_eq!(cursor.enque(0x12), Err(SerializeError::SpaceExceeded));
+        assert_eq!(cursor.get(), [0xAA, 0x41, 0x00]);
+        assert_eq!(cursor.position(), 3);
+
+        let mut serializer = SynSerializer::new(Cursor::new([0u8; 2]));
+        assert_eq!(
+            serializer.serialize_u32(1),
+            Err(SerializeError::SpaceExceeded)
+        );
+    }
+
+    #[test]
+    fn cursor_patch_u16() {
+        let mut cursor = Cursor::new([0u8; 8]);
+        for v in [0xAA, 0x41, 0x00, 0x00] {
+            assert_eq!(cursor.enque(v), Ok(()));
+        }
+        assert_eq!(cursor.patch_u16(2, 0x0012), Ok(()));
+        assert_eq!(cursor.get(), [0xAA, 0x41, 0x00, 0x12]);
+        //Only written bytes can be patched
+        assert_eq!(
+            cursor.patch_u16(3, 0x0012),
+            Err(SerializeError::SpaceExceeded)
+        );
+    }
+
+    #[cfg(feature = "heapless")]
+    #[test]
+    fn heapless_vec_space_exceeded() {
+        let mut bytes = heapless::Vec::<u8, 2>::new();
+        assert_eq!(bytes.enque(0xAA), Ok(()));
+        assert_eq!(bytes.enque(0x41), Ok(()));
+        assert_eq!(bytes.enque(0x00), Err(SerializeError::SpaceExceeded));
+        assert_eq!(ByteContainer::get(&bytes), [0xAA, 0x41]);
+    }
+
+    #[cfg(feature = "bytes")]
+    #[test]
+    fn bytes_mut_container() {
+        let mut bytes = bytes::BytesMut::new();
+        assert_eq!(bytes.enque(0xAA), Ok(()));
+        assert_eq!(ByteContainer::get(&bytes), [0xAA]);
     }
 }
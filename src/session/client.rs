@@ -0,0 +1,366 @@
+use super::{cfgcnt_changed, encode};
+use crate::{
+    config::{Cfg3Assembler, Config},
+    error::{ParseError, SessionError},
+    u24, Command, DataType, Message,
+};
+use alloc::{collections::VecDeque, vec::Vec};
+use core::time::Duration;
+
+/// State of a [`Client`]
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum ClientState {
+    Idle,           //Data transmission off
+    AwaitingConfig, //CFG-2 requested, data transmission off
+    Streaming,      //Data transmission turned on
+}
+
+/// Outcome of a message handled by a [`Client`]
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum ClientEvent {
+    Configured, //CFG-2 received and stored
+    //Data frame received while streaming, with the STAT config change bit
+    //of any of its PMUs
+    Data { config_change: bool },
+}
+
+/// PDC side of a session with the PMU or PDC sending the stream `idcode`:
+/// turns data transmission off, requests CFG-2 and turns data transmission
+/// on once it arrives. Commands are repeated when no CFG-2 or data arrives
+/// within the timeout. CFG-2 is requested again when data frames announce a
+/// configuration change, and once more when the announcement ends unless a
+/// CFG-2 with a new CFGCNT arrived in the meantime.
+#[derive(Debug)]
+pub struct Client {
+    idcode: u16,
+    timeout: Duration,
+    state: ClientState,
+    config: Option<Config>,
+    config_change: bool, //STAT config change bit of the last data frame
+    stale: bool,         //CFG-2 held predates the announced configuration change
+    deadline: Option<Duration>,
+    cfg3: Cfg3Assembler, //CFG-3 fragments received so far
+    transmit: VecDeque<Vec<u8>>,
+}
+
+impl Client {
+    pub fn new(idcode: u16, timeout: Duration) -> Client {
+        Client {
+            idcode,
+            timeout,
+            state: ClientState::Idle,
+            config: None,
+            config_change: false,
+            stale: false,
+            deadline: None,
+            cfg3: Cfg3Assembler::new(),
+            transmit: VecDeque::new(),
+        }
+    }
+
+    pub fn state(&self) -> ClientState {
+        self.state
+    }
+
+    /// Last CFG-2 received
+    pub fn config(&self) -> Option<&Config> {
+        self.config.as_ref()
+    }
+
+    /// Starts the session by turning data transmission off and requesting
+    /// CFG-2
+    pub fn connect(&mut self, now: Duration) -> Result<(), SessionError> {
+        self.send(Command::TurnOffTransmission, now)?;
+        self.request_config(now)
+    }
+
+    /// Turns data transmission off
+    pub fn disconnect(&mut self, now: Duration) -> Result<(), SessionError> {
+        self.send(Command::TurnOffTransmission, now)?;
+        self.state = ClientState::Idle;
+        self.deadline = None;
+        Ok(())
+    }
+
+    /// Queues a command frame, e.g. to request the header frame
+    pub fn send(&mut self, command: Command, now: Duration) -> Result<(), SessionError> {
+        let frame = encode(self.idcode, now, &self.time_base(), DataType::Cmd(command))?;
+        self.transmit.push_back(frame);
+        Ok(())
+    }
+
+    /// Decodes a received frame, data frames with the last CFG-2 received.
+    /// CFG-3 fragments are reassembled, giving `None` until the last one
+    /// arrives.
+    pub fn decode<'a>(&mut self, bytes: &'a [u8]) -> Result<Option<Message<'a>>, ParseError> {
+        self.cfg3.decode(bytes, self.config.as_ref())
+    }
+
+    /// Advances the session with a frame received from the stream. Frames
+    /// the session does not act on, such as header frames, give `None`.
+    pub fn handle_message(
+        &mut self,
+        message: &Message,
+        now: Duration,
+    ) -> Result<Option<ClientEvent>, SessionError> {
+        if message.idcode != self.idcode {
+            return Err(SessionError::UnexpectedIdcode);
+        }
+        match (self.state, &message.data) {
+            (ClientState::Idle, DataType::Cfg2(_) | DataType::Data(_)) => {
+                Err(SessionError::UnexpectedFrame)
+            }
+            (ClientState::AwaitingConfig, DataType::Cfg2(config)) => {
+                self.config = Some(config.clone());
+                self.send(Command::TurnOnTransmission, now)?;
+                self.state = ClientState::Streaming;
+                self.deadline = Some(now + self.timeout);
+                Ok(Some(ClientEvent::Configured))
+            }
+            //Requested on a configuration change, or sent by the server once
+            //the change takes effect
+            (ClientState::Streaming, DataType::Cfg2(config)) => {
+                if self
+                    .config
+                    .as_ref()
+                    .is_none_or(|old| cfgcnt_changed(old, config))
+                {
+                    self.stale = false;
+                }
+                self.config = Some(config.clone());
+                Ok(Some(ClientEvent::Configured))
+            }
+            (ClientState::AwaitingConfig, DataType::Data(_)) => Err(SessionError::UnexpectedFrame),
+            (ClientState::Streaming, DataType::Data(data)) => {
+                self.deadline = Some(now + self.timeout);
+                let config_change = data.pmus.iter().any(|pmu| pmu.stat().config_change);
+                let announced = config_change && !self.config_change;
+                let ended = !config_change && self.config_change;
+                self.config_change = config_change;
+                if announced {
+                    self.stale = true;
+                }
+                //Changed configuration is in effect once the bit is cleared
+                if announced || (ended && self.stale) {
+                    self.send(Command::SendCfg2, now)?;
+                }
+                Ok(Some(ClientEvent::Data { config_change }))
+            }
+            (_, DataType::Cmd(_)) => Err(SessionError::UnexpectedFrame),
+            _ => Ok(None),
+        }
+    }
+
+    /// Time at which [`Client::handle_timeout`] is due
+    pub fn poll_timeout(&self) -> Option<Duration> {
+        self.deadline
+    }
+
+    /// Requests CFG-2 again when it or data frames stopped arriving
+    pub fn handle_timeout(&mut self, now: Duration) -> Result<(), SessionError> {
+        match self.deadline {
+            Some(deadline) if deadline <= now => {
+                if self.state == ClientState::Streaming {
+                    self.send(Command::TurnOffTransmission, now)?;
+                }
+                self.request_config(now)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Next frame to write to the transport
+    pub fn poll_transmit(&mut self) -> Option<Vec<u8>> {
+        self.transmit.pop_front()
+    }
+
+    fn request_config(&mut self, now: Duration) -> Result<(), SessionError> {
+        self.send(Command::SendCfg2, now)?;
+        self.state = ClientState::AwaitingConfig;
+        self.config_change = false;
+        self.stale = false;
+        self.deadline = Some(now + self.timeout);
+        Ok(())
+    }
+
+    /// TIME_BASE of the stream, whole seconds until CFG-2 is received
+    fn time_base(&self) -> u24 {
+        match &self.config {
+            Some(config) => config.time_base.clone(),
+            None => u24::new(1).expect("TIME_BASE of 1 is in range"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod client_test {
+    use super::super::session_test::{command, config, data};
+    use super::*;
+
+    const NOW: Duration = Duration::from_secs(1_149_577_200);
+    const TIMEOUT: Duration = Duration::from_secs(5);
+
+    fn message(data: DataType<'static>) -> Message<'static> {
+        Message {
+            version: crate::FrameVersion::Std2011,
+            idcode: 7,
+            time: crate::Time::from_duration(NOW, &u24::new(1_000_000).unwrap()).unwrap(),
+            data,
+        }
+    }
+
+    fn commands(client: &mut Client) -> Vec<u16> {
+        core::iter::from_fn(|| client.poll_transmit())
+            .map(|frame| command(&frame).encode())
+            .collect()
+    }
+
+    #[test]
+    fn configures_and_turns_on_data() {
+        let mut client = Client::new(7, TIMEOUT);
+        client.connect(NOW).unwrap();
+        assert_eq!(client.state(), ClientState::AwaitingConfig);
+        assert_eq!(commands(&mut client), [0x0001, 0x0005]);
+        assert_eq!(client.poll_timeout(), Some(NOW + TIMEOUT));
+
+        //Data before CFG-2 cannot be interpreted
+        assert_eq!(
+            client.handle_message(&message(DataType::Data(data(0))), NOW),
+            Err(SessionError::UnexpectedFrame)
+        );
+        assert_eq!(
+            client.handle_message(&message(DataType::Cfg2(config(1))), NOW),
+            Ok(Some(ClientEvent::Configured))
+        );
+        assert_eq!(commands(&mut client), [0x0002]);
+        assert_eq!(client.state(), ClientState::Streaming);
+        assert_eq!(
+            client.handle_message(&message(DataType::Data(data(0))), NOW + TIMEOUT),
+            Ok(Some(ClientEvent::Data {
+                config_change: false
+            }))
+        );
+        assert_eq!(client.poll_timeout(), Some(NOW + TIMEOUT * 2));
+
+        let mut other = message(DataType::Data(data(0)));
+        other.idcode = 8;
+        assert_eq!(
+            client.handle_message(&other, NOW),
+            Err(SessionError::UnexpectedIdcode)
+        );
+    }
+
+    #[test]
+    fn decodes_fragmented_cfg3() {
+        use crate::config::*;
+        let config3 = Config3 {
+            time_base: u24::new(1_000_000).unwrap(),
+            pmus: alloc::vec![PmuConfig3 {
+                station: "STATION A".into(),
+                idcode: 7,
+                g_pmu_id: [0; 16],
+                format: Format::decode(0x0000).unwrap(),
+                phasors: alloc::vec![],
+                analogs: alloc::vec![],
+                digitals: alloc::vec![],
+                latitude: f32::INFINITY,
+                longitude: f32::INFINITY,
+                elevation: f32::INFINITY,
+                service_class: ServiceClass::Measurement,
+                window: 0,
+                group_delay: 0,
+                fnom: NominalFrequency::Hz60,
+                cfgcnt: 1,
+            }],
+            data_rate: 10,
+        };
+        let frames: Vec<_> = config3
+            .split(16)
+            .unwrap()
+            .into_iter()
+            .map(|payload| encode(7, NOW, &u24::new(1).unwrap(), DataType::Cfg3(payload)).unwrap())
+            .collect();
+        assert!(frames.len() > 1);
+
+        let mut client = Client::new(7, TIMEOUT);
+        let (last, fragments) = frames.split_last().unwrap();
+        for frame in fragments {
+            assert_eq!(client.decode(frame), Ok(None));
+        }
+        assert_eq!(
+            client.decode(last).map(|m| m.map(|m| m.data)),
+            Ok(Some(DataType::Cfg3(Cfg3Payload::Config(config3))))
+        );
+    }
+
+    #[test]
+    fn repeats_requests_on_timeout() {
+        let mut client = Client::new(7, TIMEOUT);
+        client.connect(NOW).unwrap();
+        commands(&mut client);
+
+        client.handle_timeout(NOW + TIMEOUT / 2).unwrap();
+        assert_eq!(commands(&mut client), []);
+        client.handle_timeout(NOW + TIMEOUT).unwrap();
+        assert_eq!(commands(&mut client), [0x0005]);
+
+        //Data stopped arriving
+        client
+            .handle_message(&message(DataType::Cfg2(config(1))), NOW + TIMEOUT)
+            .unwrap();
+        commands(&mut client);
+        client.handle_timeout(NOW + TIMEOUT * 2).unwrap();
+        assert_eq!(commands(&mut client), [0x0001, 0x0005]);
+        assert_eq!(client.state(), ClientState::AwaitingConfig);
+    }
+
+    #[test]
+    fn requests_config_around_change() {
+        let mut client = Client::new(7, TIMEOUT);
+        client.connect(NOW).unwrap();
+        client
+            .handle_message(&message(DataType::Cfg2(config(1))), NOW)
+            .unwrap();
+        commands(&mut client);
+
+        let changing = message(DataType::Data(data(1 << 10)));
+        let changed = message(DataType::Data(data(0)));
+        for _ in 0..2 {
+            assert_eq!(
+                client.handle_message(&changing, NOW),
+                Ok(Some(ClientEvent::Data {
+                    config_change: true
+                }))
+            );
+        }
+        assert_eq!(commands(&mut client), [0x0005]);
+        //Change has not taken effect yet
+        assert_eq!(
+            client.handle_message(&message(DataType::Cfg2(config(1))), NOW),
+            Ok(Some(ClientEvent::Configured))
+        );
+        assert_eq!(commands(&mut client), []);
+        assert_eq!(
+            client.handle_message(&changed, NOW),
+            Ok(Some(ClientEvent::Data {
+                config_change: false
+            }))
+        );
+        assert_eq!(commands(&mut client), [0x0005]);
+        assert_eq!(
+            client.handle_message(&message(DataType::Cfg2(config(2))), NOW),
+            Ok(Some(ClientEvent::Configured))
+        );
+        assert_eq!(client.config(), Some(&config(2)));
+        assert_eq!(commands(&mut client), []);
+
+        //New CFGCNT received before the announcement ends
+        client.handle_message(&changing, NOW).unwrap();
+        client
+            .handle_message(&message(DataType::Cfg2(config(3))), NOW)
+            .unwrap();
+        client.handle_message(&changed, NOW).unwrap();
+        assert_eq!(commands(&mut client), [0x0005]);
+        assert_eq!(client.config(), Some(&config(3)));
+    }
+}
@@ -0,0 +1,181 @@
+//! Session logic of clause 7 of IEEE C37.118.2, independent of the transport.
+//!
+//! [`Client`] runs the PDC side and [`Server`] the PMU side. Both consume
+//! decoded messages and queue encoded frames, which the caller takes with
+//! `poll_transmit` and writes to its transport. Time is passed in as the time
+//! since the UNIX epoch, also used to stamp outgoing frames. `poll_timeout`
+//! returns the time at which `handle_timeout` is to be called next.
+
+use crate::{
+    error::SessionError, u24, Config, DataType, FrameVersion, Message, SynSerializer, Time,
+};
+use alloc::vec::Vec;
+use core::time::Duration;
+
+mod client;
+mod server;
+pub use client::*;
+pub use server::*;
+
+/// Frame of `data` from the stream `idcode`, stamped with `now`
+fn encode(
+    idcode: u16,
+    now: Duration,
+    time_base: &u24,
+    data: DataType,
+) -> Result<Vec<u8>, SessionError> {
+    let message = Message {
+        version: FrameVersion::Std2011,
+        idcode,
+        time: Time::from_duration(now, time_base)?,
+        data,
+    };
+    Ok(SynSerializer::new(Vec::new()).to_bytes(&message)?)
+}
+
+/// CFGCNT of any PMU differs, or the PMUs differ
+fn cfgcnt_changed(old: &Config, new: &Config) -> bool {
+    !old.pmus
+        .iter()
+        .map(|pmu| pmu.cfgcnt)
+        .eq(new.pmus.iter().map(|pmu| pmu.cfgcnt))
+}
+
+#[cfg(test)]
+mod session_test {
+    use super::*;
+    use crate::{
+        config::*,
+        deserializer::from_bytes,
+        message::{DataFrame, PmuData, Scalar},
+        Command,
+    };
+
+    pub(super) fn config(cfgcnt: u16) -> Config {
+        Config {
+            time_base: u24::new(1_000_000).unwrap(),
+            pmus: alloc::vec![PmuConfig {
+                station: Name::new("STATION A").unwrap(),
+                idcode: 7,
+                format: Format {
+                    freq: NumberFormat::Int,
+                    analog: NumberFormat::Int,
+                    phasor: NumberFormat::Int,
+                    phasor_notation: PhasorNotation::Rectangular,
+                },
+                phasors: alloc::vec![],
+                analogs: alloc::vec![],
+                digitals: alloc::vec![],
+                fnom: NominalFrequency::Hz60,
+                cfgcnt,
+            }],
+            data_rate: 10,
+        }
+    }
+
+    pub(super) fn data(stat: u16) -> DataFrame {
+        DataFrame {
+            pmus: alloc::vec![PmuData {
+                stat_raw: stat,
+                phasors: alloc::vec![],
+                freq: Scalar::Int(0),
+                dfreq: Scalar::Int(0),
+                analog: alloc::vec![],
+                digital: alloc::vec![],
+            }],
+        }
+    }
+
+    pub(super) fn command(bytes: &[u8]) -> Command<'_> {
+        match from_bytes(bytes).unwrap().data {
+            DataType::Cmd(command) => command,
+            data => panic!("Expected command frame, got {:?}", data),
+        }
+    }
+
+    #[test]
+    fn client_server_session() {
+        let mut now = Duration::from_secs(1_149_577_200);
+        let mut client = Client::new(7, Duration::from_secs(5));
+        let mut server = Server::new(7, config(1), crate::Header::new("PMU Station A"));
+
+        //Frames are passed as bytes in both directions
+        fn exchange(
+            client: &mut Client,
+            server: &mut Server,
+            now: Duration,
+        ) -> (Vec<ClientEvent>, Vec<ServerEvent>) {
+            let (mut client_events, mut server_events) = (Vec::new(), Vec::new());
+            while let Some(frame) = client.poll_transmit() {
+                let message = from_bytes(&frame).unwrap();
+                server_events.extend(server.handle_message(&message, now).unwrap());
+            }
+            while let Some(frame) = server.poll_transmit() {
+                if let Some(message) = client.decode(&frame).unwrap() {
+                    client_events.extend(client.handle_message(&message, now).unwrap());
+                }
+            }
+            (client_events, server_events)
+        }
+
+        client.connect(now).unwrap();
+        assert_eq!(
+            exchange(&mut client, &mut server, now),
+            (alloc::vec![ClientEvent::Configured], alloc::vec![])
+        );
+        assert_eq!(client.config(), Some(server.config()));
+        assert_eq!(
+            exchange(&mut client, &mut server, now),
+            (alloc::vec![], alloc::vec![ServerEvent::TransmissionOn])
+        );
+        assert_eq!(client.state(), ClientState::Streaming);
+
+        now = server.poll_timeout().unwrap();
+        assert_eq!(server.handle_timeout(now), Ok(Some(ServerEvent::DataDue)));
+        server.send_data(data(0), now).unwrap();
+        let data_event = |config_change| ClientEvent::Data { config_change };
+        assert_eq!(
+            exchange(&mut client, &mut server, now),
+            (alloc::vec![data_event(false)], alloc::vec![])
+        );
+
+        //Configuration change is flagged in STAT a minute ahead
+        server.set_config(config(2), now);
+        server.send_data(data(0), now).unwrap();
+        assert_eq!(
+            exchange(&mut client, &mut server, now),
+            (alloc::vec![data_event(true)], alloc::vec![])
+        );
+        assert_eq!(
+            exchange(&mut client, &mut server, now),
+            (alloc::vec![ClientEvent::Configured], alloc::vec![])
+        );
+        assert_eq!(client.config(), Some(&config(1)));
+
+        //New configuration is sent unsolicited once it takes effect
+        now += Duration::from_secs(60);
+        assert_eq!(
+            server.handle_timeout(now),
+            Ok(Some(ServerEvent::ConfigChanged))
+        );
+        assert_eq!(server.handle_timeout(now), Ok(Some(ServerEvent::DataDue)));
+        server.send_data(data(0), now).unwrap();
+        assert_eq!(
+            exchange(&mut client, &mut server, now),
+            (
+                alloc::vec![ClientEvent::Configured, data_event(false)],
+                alloc::vec![]
+            )
+        );
+        assert_eq!(client.config(), Some(&config(2)));
+        assert_eq!(client.poll_transmit(), None);
+
+        client.disconnect(now).unwrap();
+        assert_eq!(
+            exchange(&mut client, &mut server, now),
+            (alloc::vec![], alloc::vec![ServerEvent::TransmissionOff])
+        );
+        assert_eq!(server.poll_timeout(), None);
+        assert_eq!(client.poll_timeout(), None);
+    }
+}
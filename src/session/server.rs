@@ -0,0 +1,403 @@
+use super::{cfgcnt_changed, encode};
+use crate::{
+    config::Config,
+    error::SessionError,
+    message::{DataFrame, Header},
+    Command, DataType, Message,
+};
+use alloc::{collections::VecDeque, vec::Vec};
+use core::time::Duration;
+
+/// STAT config change bit is set for a minute before a configuration change
+/// takes effect
+const CONFIG_CHANGE_NOTICE: Duration = Duration::from_secs(60);
+
+/// Outcome of a message or timeout handled by a [`Server`]
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum ServerEvent {
+    TransmissionOn,  //Data transmission turned on
+    TransmissionOff, //Data transmission turned off
+    DataDue,         //Next data frame is to be sent with Server::send_data
+    ConfigChanged,   //Configuration set with Server::set_config took effect
+    Command(u16),    //CMD left to the application, such as CFG-3 requests
+}
+
+/// PMU side of a session for the stream `idcode`: answers header and
+/// configuration requests, and paces data frames at DATA_RATE while data
+/// transmission is on.
+#[derive(Debug)]
+pub struct Server {
+    idcode: u16,
+    config: Config,
+    header: Header<'static>,
+    transmitting: bool,
+    next_data: Option<Duration>,         //Time the next data frame is due
+    pending: Option<(Config, Duration)>, //Configuration change and the time it takes effect
+    transmit: VecDeque<Vec<u8>>,
+}
+
+impl Server {
+    pub fn new(idcode: u16, config: Config, header: Header<'static>) -> Server {
+        Server {
+            idcode,
+            config,
+            header,
+            transmitting: false,
+            next_data: None,
+            pending: None,
+            transmit: VecDeque::new(),
+        }
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    pub fn is_transmitting(&self) -> bool {
+        self.transmitting
+    }
+
+    /// Replaces the configuration. When CFGCNT of any PMU changes, the
+    /// change takes effect a minute after `now`: until then the current
+    /// configuration is served and data frames flag the change in STAT.
+    /// CFG-2 is sent once the change takes effect while data transmission is
+    /// on. Otherwise the configuration is replaced at once.
+    pub fn set_config(&mut self, config: Config, now: Duration) {
+        if cfgcnt_changed(&self.config, &config) {
+            self.pending = Some((config, now + CONFIG_CHANGE_NOTICE));
+        } else {
+            self.config = config;
+            self.pending = None;
+        }
+    }
+
+    /// Handles a command frame from the client
+    pub fn handle_message(
+        &mut self,
+        message: &Message,
+        now: Duration,
+    ) -> Result<Option<ServerEvent>, SessionError> {
+        if message.idcode != self.idcode {
+            return Err(SessionError::UnexpectedIdcode);
+        }
+        let DataType::Cmd(command) = &message.data else {
+            return Err(SessionError::UnexpectedFrame);
+        };
+        match command {
+            Command::TurnOffTransmission => {
+                let was_transmitting = core::mem::take(&mut self.transmitting);
+                self.next_data = None;
+                Ok(was_transmitting.then_some(ServerEvent::TransmissionOff))
+            }
+            Command::TurnOnTransmission => {
+                let was_transmitting = core::mem::replace(&mut self.transmitting, true);
+                if !was_transmitting {
+                    self.next_data = next_frame(now, self.config.data_rate);
+                }
+                Ok((!was_transmitting).then_some(ServerEvent::TransmissionOn))
+            }
+            Command::SendHeader => {
+                self.send(DataType::Header(self.header.clone()), now)?;
+                Ok(None)
+            }
+            Command::SendCfg1 => {
+                self.send(DataType::Cfg1(self.config.clone()), now)?;
+                Ok(None)
+            }
+            Command::SendCfg2 => {
+                self.send(DataType::Cfg2(self.config.clone()), now)?;
+                Ok(None)
+            }
+            command => Ok(Some(ServerEvent::Command(command.encode()))),
+        }
+    }
+
+    /// Queues a frame, e.g. CFG-3 fragments in answer to a command
+    pub fn send(&mut self, data: DataType, now: Duration) -> Result<(), SessionError> {
+        let frame = encode(self.idcode, now, &self.config.time_base, data)?;
+        self.transmit.push_back(frame);
+        Ok(())
+    }
+
+    /// Queues a data frame, with the STAT config change bit set while a
+    /// configuration change is pending. The change only takes effect in
+    /// [`Server::handle_timeout`], so `data` matches the configuration until
+    /// [`ServerEvent::ConfigChanged`] is reported. Data frames are dropped
+    /// while data transmission is off.
+    pub fn send_data(&mut self, mut data: DataFrame, now: Duration) -> Result<(), SessionError> {
+        if !self.transmitting {
+            return Ok(());
+        }
+        //PMU blocks must match the configuration
+        data.blocks(&self.config).map(|_| ())?;
+        if self.pending.is_some() {
+            for pmu in data.pmus.iter_mut() {
+                let mut stat = pmu.stat();
+                stat.config_change = true;
+                pmu.stat_raw = stat.encode();
+            }
+        }
+        self.send(DataType::Data(data), now)
+    }
+
+    /// Time at which [`Server::handle_timeout`] is due
+    pub fn poll_timeout(&self) -> Option<Duration> {
+        let change = self.pending.as_ref().map(|(_, at)| *at);
+        self.next_data.into_iter().chain(change).min()
+    }
+
+    /// Applies a pending configuration change once it is due, otherwise
+    /// reports when the next data frame is due. Frames missed since the last
+    /// call are skipped.
+    pub fn handle_timeout(&mut self, now: Duration) -> Result<Option<ServerEvent>, SessionError> {
+        if self.change_config(now)? {
+            return Ok(Some(ServerEvent::ConfigChanged));
+        }
+        let Some(due) = self.next_data.filter(|&next| next <= now) else {
+            return Ok(None);
+        };
+        self.next_data = next_frame(due.max(now), self.config.data_rate);
+        Ok(Some(ServerEvent::DataDue))
+    }
+
+    /// Next frame to write to the transport
+    pub fn poll_transmit(&mut self) -> Option<Vec<u8>> {
+        self.transmit.pop_front()
+    }
+
+    /// Replaces the configuration with the pending one once it is due, and
+    /// restarts data frame pacing if DATA_RATE changed
+    fn change_config(&mut self, now: Duration) -> Result<bool, SessionError> {
+        let previous = match self.pending.take() {
+            Some((config, at)) if at <= now => core::mem::replace(&mut self.config, config),
+            pending => {
+                self.pending = pending;
+                return Ok(false);
+            }
+        };
+        if self.transmitting {
+            if previous.data_rate != self.config.data_rate {
+                self.next_data = next_frame(now, self.config.data_rate);
+            }
+            self.send(DataType::Cfg2(self.config.clone()), now)?;
+        }
+        Ok(true)
+    }
+}
+
+/// First data frame time after `now`. Positive DATA_RATE is frames per
+/// second, aligned to the second; negative is seconds per frame.
+fn next_frame(now: Duration, data_rate: i16) -> Option<Duration> {
+    const NANOS_PER_SEC: u64 = 1_000_000_000;
+    let secs = now.as_secs();
+    match data_rate {
+        0 => None,
+        rate if rate > 0 => {
+            let rate = rate as u64;
+            let frame = now.subsec_nanos() as u64 * rate / NANOS_PER_SEC + 1;
+            let (secs, frame) = if frame >= rate {
+                (secs + 1, 0)
+            } else {
+                (secs, frame)
+            };
+            //Rounded up so the frame time is never before now
+            let nanos = (frame * NANOS_PER_SEC).div_ceil(rate);
+            Some(Duration::from_secs(secs) + Duration::from_nanos(nanos))
+        }
+        rate => {
+            let period = rate.unsigned_abs() as u64;
+            Some(Duration::from_secs((secs / period + 1) * period))
+        }
+    }
+}
+
+#[cfg(test)]
+mod server_test {
+    use super::super::session_test::{config, data};
+    use super::*;
+    use crate::deserializer::{from_bytes, from_bytes_with_config};
+
+    const NOW: Duration = Duration::from_secs(1_149_577_200);
+
+    fn message(command: Command<'static>) -> Message<'static> {
+        Message {
+            version: crate::FrameVersion::Std2011,
+            idcode: 7,
+            time: crate::Time::from_duration(NOW, &crate::u24::new(1).unwrap()).unwrap(),
+            data: DataType::Cmd(command),
+        }
+    }
+
+    fn server() -> Server {
+        Server::new(7, config(1), Header::new("PMU Station A"))
+    }
+
+    #[test]
+    fn answers_requests() {
+        let mut server = server();
+        for command in [Command::SendHeader, Command::SendCfg1, Command::SendCfg2] {
+            assert_eq!(server.handle_message(&message(command), NOW), Ok(None));
+        }
+        let frames: Vec<_> = core::iter::from_fn(|| server.poll_transmit()).collect();
+        let received: Vec<_> = frames
+            .iter()
+            .map(|frame| from_bytes(frame).unwrap().data)
+            .collect();
+        assert_eq!(
+            received,
+            [
+                DataType::Header(Header::new("PMU Station A")),
+                DataType::Cfg1(config(1)),
+                DataType::Cfg2(config(1)),
+            ]
+        );
+
+        assert_eq!(
+            server.handle_message(&message(Command::SendCfg3), NOW),
+            Ok(Some(ServerEvent::Command(0x0006)))
+        );
+        let mut data_frame = message(Command::SendCfg2);
+        data_frame.data = DataType::Data(data(0));
+        assert_eq!(
+            server.handle_message(&data_frame, NOW),
+            Err(SessionError::UnexpectedFrame)
+        );
+    }
+
+    #[test]
+    fn paces_data_frames() {
+        let mut server = server();
+        server.send_data(data(0), NOW).unwrap();
+        assert_eq!(server.poll_transmit(), None);
+        assert_eq!(server.poll_timeout(), None);
+
+        let start = NOW + Duration::from_millis(250);
+        assert_eq!(
+            server.handle_message(&message(Command::TurnOnTransmission), start),
+            Ok(Some(ServerEvent::TransmissionOn))
+        );
+        //10 frames per second
+        let due = NOW + Duration::from_millis(300);
+        assert_eq!(server.poll_timeout(), Some(due));
+        assert_eq!(server.handle_timeout(start), Ok(None));
+        assert_eq!(server.handle_timeout(due), Ok(Some(ServerEvent::DataDue)));
+        assert_eq!(
+            server.poll_timeout(),
+            Some(due + Duration::from_millis(100))
+        );
+        server.send_data(data(0), due).unwrap();
+        let frame = server.poll_transmit().unwrap();
+        assert_eq!(
+            from_bytes_with_config(&frame, &config(1)).map(|m| m.data),
+            Ok(DataType::Data(data(0)))
+        );
+
+        assert_eq!(
+            server.handle_message(&message(Command::TurnOffTransmission), due),
+            Ok(Some(ServerEvent::TransmissionOff))
+        );
+        assert_eq!(server.poll_timeout(), None);
+    }
+
+    #[test]
+    fn announces_config_change() {
+        let mut server = server();
+        server
+            .handle_message(&message(Command::TurnOnTransmission), NOW)
+            .unwrap();
+        server.set_config(config(1), NOW);
+        assert_eq!(
+            server.poll_timeout(),
+            Some(NOW + Duration::from_millis(100))
+        );
+
+        let stat = |server: &mut Server, now| {
+            server.send_data(data(0), now).unwrap();
+            let frame = server.poll_transmit().unwrap();
+            match from_bytes_with_config(&frame, server.config())
+                .unwrap()
+                .data
+            {
+                DataType::Data(data) => data.pmus[0].stat().config_change,
+                _ => unreachable!(),
+            }
+        };
+        assert!(!stat(&mut server, NOW));
+
+        //Old configuration is served with the change flagged for a minute
+        server.set_config(config(2), NOW);
+        let change = NOW + CONFIG_CHANGE_NOTICE;
+        assert_eq!(server.poll_transmit(), None);
+        assert_eq!(server.config(), &config(1));
+        assert!(stat(&mut server, change - Duration::from_millis(100)));
+        assert_eq!(
+            server.poll_timeout(),
+            Some(NOW + Duration::from_millis(100))
+        );
+        assert_eq!(
+            server.handle_timeout(change - Duration::from_millis(100)),
+            Ok(Some(ServerEvent::DataDue))
+        );
+        assert_eq!(server.poll_timeout(), Some(change));
+        assert_eq!(server.config(), &config(1));
+
+        //Data for the old configuration is accepted until the change is reported
+        assert!(stat(&mut server, change));
+        assert_eq!(server.config(), &config(1));
+
+        //Change takes effect before the data frame due at the same time
+        assert_eq!(
+            server.handle_timeout(change),
+            Ok(Some(ServerEvent::ConfigChanged))
+        );
+        let frame = server.poll_transmit().unwrap();
+        assert_eq!(
+            from_bytes(&frame).map(|m| m.data),
+            Ok(DataType::Cfg2(config(2)))
+        );
+        assert_eq!(server.config(), &config(2));
+        assert_eq!(
+            server.handle_timeout(change),
+            Ok(Some(ServerEvent::DataDue))
+        );
+        assert!(!stat(&mut server, change));
+    }
+
+    #[test]
+    fn config_change_restarts_pacing() {
+        let mut server = server();
+        server
+            .handle_message(&message(Command::TurnOnTransmission), NOW)
+            .unwrap();
+        let mut slower = config(2);
+        slower.data_rate = 5;
+        server.set_config(slower.clone(), NOW);
+        let change = NOW + CONFIG_CHANGE_NOTICE;
+        assert_eq!(
+            server.handle_timeout(change - Duration::from_millis(100)),
+            Ok(Some(ServerEvent::DataDue))
+        );
+        assert_eq!(
+            server.handle_timeout(change),
+            Ok(Some(ServerEvent::ConfigChanged))
+        );
+        assert_eq!(server.config(), &slower);
+        assert_eq!(
+            server.poll_timeout(),
+            Some(change + Duration::from_millis(200))
+        );
+    }
+
+    #[test]
+    fn data_rate_frame_times() {
+        let at = |millis| Some(NOW + Duration::from_millis(millis));
+        assert_eq!(next_frame(NOW, 10), at(100));
+        assert_eq!(next_frame(NOW + Duration::from_millis(950), 10), at(1000));
+        assert_eq!(
+            next_frame(NOW, 30),
+            Some(NOW + Duration::from_nanos(33_333_334))
+        );
+        assert_eq!(next_frame(NOW + Duration::from_millis(500), -5), at(5000));
+        assert_eq!(next_frame(NOW, 0), None);
+    }
+}
@@ -0,0 +1,277 @@
+#![allow(dead_code)]
+/*!
+ * Frames shared by the serialization and deserialization tests
+ */
+#[cfg(feature = "alloc")]
+use serde_synphasor::config::*;
+use serde_synphasor::*;
+
+/// 16 byte CHNAM/STN field, padded with spaces
+fn name(name: &str) -> [u8; 16] {
+    let mut bytes = [b' '; 16];
+    bytes[..name.len()].copy_from_slice(name.as_bytes());
+    bytes
+}
+
+/// CFG-2 frame of the configuration frame example in Annex D of
+/// IEEE C37.118: one PMU with 4 phasors, 3 analog values and 1 digital status word.
+pub fn annex_cfg2_frame() -> Vec<u8> {
+    let mut bytes = vec![
+        0xAA, 0x31, 0x01, 0xC6, 0x00, 0x07, 0x44, 0x85, 0x36, 0x00, 0x00, 0x00, 0x41, 0xB1,
+    ];
+    //TIME_BASE, NUM_PMU
+    bytes.extend_from_slice(&[0x00, 0x0F, 0x42, 0x40, 0x00, 0x01]);
+    bytes.extend_from_slice(&name("Station A"));
+    //IDCODE, FORMAT, PHNMR, ANNMR, DGNMR
+    bytes.extend_from_slice(&[0x00, 0x07, 0x00, 0x04, 0x00, 0x04, 0x00, 0x03, 0x00, 0x01]);
+    for chnam in ["VA", "VB", "VC", "I1", "ANALOG1", "ANALOG2", "ANALOG3"] {
+        bytes.extend_from_slice(&name(chnam));
+    }
+    for c in "123456789ABCDEFG".chars() {
+        bytes.extend_from_slice(&name(&format!("BREAKER {} STATUS", c)));
+    }
+    //PHUNIT
+    for _ in 0..3 {
+        bytes.extend_from_slice(&[0x00, 0x0D, 0xF8, 0x47]);
+    }
+    bytes.extend_from_slice(&[0x01, 0x00, 0xB2, 0xD0]);
+    //ANUNIT: point-on-wave, RMS and peak
+    bytes.extend_from_slice(&[
+        0x00, 0x00, 0x00, 0x01, 0x01, 0x00, 0x00, 0x01, 0x02, 0x00, 0x00, 0x01,
+    ]);
+    //DIGUNIT, FNOM, CFGCNT, DATA_RATE, CHK
+    bytes.extend_from_slice(&[
+        0x00, 0x00, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x16, 0x00, 0x1E, 0x30, 0x6A,
+    ]);
+    bytes
+}
+
+#[cfg(feature = "alloc")]
+pub fn annex_cfg2_config() -> Config {
+    let phasor = |chnam: &str, kind: PhasorKind, factor: u32| PhasorChannel {
+        name: Name::new(chnam).unwrap(),
+        unit: PhasorUnit {
+            kind,
+            factor: u24::new(factor).unwrap(),
+        },
+    };
+    let analog = |chnam: &str, kind: AnalogKind| AnalogChannel {
+        name: Name::new(chnam).unwrap(),
+        unit: AnalogUnit {
+            kind,
+            factor: i24::new(1).unwrap(),
+        },
+    };
+    Config {
+        time_base: u24::new(1_000_000).unwrap(),
+        pmus: vec![PmuConfig {
+            station: Name::new("Station A").unwrap(),
+            idcode: 7,
+            format: Format {
+                freq: NumberFormat::Int,
+                analog: NumberFormat::Float,
+                phasor: NumberFormat::Int,
+                phasor_notation: PhasorNotation::Rectangular,
+            },
+            phasors: vec![
+                phasor("VA", PhasorKind::Voltage, 915527),
+                phasor("VB", PhasorKind::Voltage, 915527),
+                phasor("VC", PhasorKind::Voltage, 915527),
+                phasor("I1", PhasorKind::Current, 45776),
+            ],
+            analogs: vec![
+                analog("ANALOG1", AnalogKind::PointOnWave),
+                analog("ANALOG2", AnalogKind::Rms),
+                analog("ANALOG3", AnalogKind::Peak),
+            ],
+            digitals: vec![DigitalChannel {
+                names: core::array::from_fn(|i| {
+                    let c = "123456789ABCDEFG".as_bytes()[i] as char;
+                    Name::new(&format!("BREAKER {} STATUS", c)).unwrap()
+                }),
+                unit: DigitalUnit {
+                    normal: 0x0000,
+                    valid: 0xFFFF,
+                },
+            }],
+            fnom: NominalFrequency::Hz60,
+            cfgcnt: 22,
+        }],
+        data_rate: 30,
+    }
+}
+
+#[cfg(feature = "alloc")]
+pub fn annex_cfg2_message() -> Message<'static> {
+    Message {
+        version: FrameVersion::Std2005,
+        idcode: 7,
+        time: Time {
+            soc: 1_149_580_800,
+            fracsec: u24::new(16817).unwrap(),
+            leap_second_direction: false,
+            leap_second_occured: false,
+            leap_second_pending: false,
+            time_quality: TimeQuality::Locked,
+        },
+        data: DataType::Cfg2(annex_cfg2_config()),
+    }
+}
+
+fn utf8_name(bytes: &mut Vec<u8>, name: &str) {
+    bytes.push(name.len() as u8);
+    bytes.extend_from_slice(name.as_bytes());
+}
+
+/// CFG-3 frame for one PMU with 2 phasors, 1 analog value and 1 digital
+/// status word of which only the first two channels are named.
+pub fn cfg3_frame() -> Vec<u8> {
+    let mut bytes = vec![
+        0xAA, 0x52, 0x00, 0xAC, 0x00, 0x07, 0x44, 0x85, 0x36, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    //CONT_IDX, TIME_BASE, NUM_PMU
+    bytes.extend_from_slice(&[0x00, 0x00, 0x00, 0x0F, 0x42, 0x40, 0x00, 0x01]);
+    utf8_name(&mut bytes, "Station Ä");
+    //IDCODE, G_PMU_ID
+    bytes.extend_from_slice(&[0x00, 0x07]);
+    bytes.extend(0..16u8);
+    //FORMAT, PHNMR, ANNMR, DGNMR
+    bytes.extend_from_slice(&[0x00, 0x04, 0x00, 0x02, 0x00, 0x01, 0x00, 0x01]);
+    for chnam in ["VA", "I1", "ANALOG1", "DIGITAL 1", "DIGITAL 2"] {
+        utf8_name(&mut bytes, chnam);
+    }
+    bytes.extend_from_slice(&[0; 14]);
+    //PHSCALE
+    bytes.extend_from_slice(&[
+        0x00, 0x00, 0x00, 0x01, 0x3D, 0xBB, 0x7F, 0xFB, 0x00, 0x00, 0x00, 0x00,
+    ]);
+    bytes.extend_from_slice(&[
+        0x00, 0x00, 0x00, 0x09, 0x3B, 0x95, 0xFF, 0xB1, 0x00, 0x00, 0x00, 0x00,
+    ]);
+    //ANSCALE, DIGUNIT
+    bytes.extend_from_slice(&[
+        0x3F, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xFF, 0xFF,
+    ]);
+    //PMU_LAT, PMU_LON, PMU_ELEV, SVC_CLASS, WINDOW, GRP_DLY
+    bytes.extend_from_slice(&[
+        0x42, 0x3A, 0x00, 0x00, 0xC2, 0xA2, 0x80, 0x00, 0x7F, 0x80, 0x00, 0x00, 0x4D, 0x00, 0x01,
+        0x45, 0x85, 0x00, 0x00, 0x00, 0x00,
+    ]);
+    //FNOM, CFGCNT, DATA_RATE, CHK
+    bytes.extend_from_slice(&[0x00, 0x00, 0x00, 0x16, 0x00, 0x1E, 0xCB, 0x32]);
+    bytes
+}
+
+#[cfg(feature = "alloc")]
+pub fn cfg3_config() -> Config3 {
+    let phasor = |chnam: &str, kind: PhasorKind, magnitude: f32| PhasorChannel3 {
+        name: chnam.to_string(),
+        scale: PhasorScale {
+            modification: 0,
+            user: 0,
+            kind,
+            component: PhasorComponent::PositiveSequence,
+            magnitude,
+            angle: 0.0,
+        },
+    };
+    let mut digital_names: [String; 16] = Default::default();
+    digital_names[0] = "DIGITAL 1".to_string();
+    digital_names[1] = "DIGITAL 2".to_string();
+    Config3 {
+        time_base: u24::new(1_000_000).unwrap(),
+        pmus: vec![PmuConfig3 {
+            station: "Station Ä".to_string(),
+            idcode: 7,
+            g_pmu_id: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+            format: Format {
+                freq: NumberFormat::Int,
+                analog: NumberFormat::Float,
+                phasor: NumberFormat::Int,
+                phasor_notation: PhasorNotation::Rectangular,
+            },
+            phasors: vec![
+                phasor("VA", PhasorKind::Voltage, 0.0915527),
+                phasor("I1", PhasorKind::Current, 0.0045776),
+            ],
+            analogs: vec![AnalogChannel3 {
+                name: "ANALOG1".to_string(),
+                scale: AnalogScale {
+                    magnitude: 1.0,
+                    offset: 0.0,
+                },
+            }],
+            digitals: vec![DigitalChannel3 {
+                names: digital_names,
+                unit: DigitalUnit {
+                    normal: 0x0000,
+                    valid: 0xFFFF,
+                },
+            }],
+            latitude: 46.5,
+            longitude: -81.25,
+            elevation: f32::INFINITY,
+            service_class: ServiceClass::Measurement,
+            window: 83333,
+            group_delay: 0,
+            fnom: NominalFrequency::Hz60,
+            cfgcnt: 22,
+        }],
+        data_rate: 30,
+    }
+}
+
+#[cfg(feature = "alloc")]
+pub fn cfg3_message() -> Message<'static> {
+    let mut message = annex_cfg2_message();
+    message.version = FrameVersion::Std2011;
+    message.time.fracsec = u24::new(0).unwrap();
+    message.data = DataType::Cfg3(Cfg3Payload::Config(cfg3_config()));
+    message
+}
+
+/// Rewrites FRAMESIZE and CHK so an edited frame passes both checks
+pub fn refresh_frame(bytes: &mut [u8]) {
+    let _ = checksum::finalize_frame(bytes);
+}
+
+/// Data frame of the data frame example in Annex D of IEEE C37.118,
+/// matching [`annex_cfg2_config`]
+pub fn annex_data_frame() -> Vec<u8> {
+    let mut bytes = vec![
+        0xAA, 0x01, 0x00, 0x34, 0x00, 0x07, 0x44, 0x85, 0x36, 0x00, 0x00, 0x00, 0x41, 0xB1,
+    ];
+    //STAT, PHASORS
+    bytes.extend_from_slice(&[
+        0x00, 0x00, 0x39, 0x2B, 0x00, 0x00, 0xE3, 0x6A, 0xCE, 0x7C, 0xE3, 0x6A, 0x31, 0x83, 0x04,
+        0x44, 0x00, 0x00,
+    ]);
+    //FREQ, DFREQ, ANALOG
+    bytes.extend_from_slice(&[
+        0x09, 0xC4, 0x00, 0x00, 0x42, 0xC8, 0x00, 0x00, 0x44, 0x7A, 0x00, 0x00, 0x46, 0x1C, 0x40,
+        0x00,
+    ]);
+    //DIGITAL, CHK
+    bytes.extend_from_slice(&[0x3C, 0x12, 0x5F, 0x58]);
+    bytes
+}
+
+/// Command frame of the command frame example in Annex D of IEEE C37.118:
+/// send CFG-2 to the PMU with IDCODE 7
+pub fn annex_command_frame() -> Vec<u8> {
+    vec![
+        0xAA, 0x41, 0x00, 0x12, 0x00, 0x07, 0x44, 0x85, 0x36, 0x00, 0x00, 0x00, 0x41, 0xB1, 0x00,
+        0x05, 0x14, 0xBD,
+    ]
+}
+
+/// Header frame carrying "PMU Station A 230"
+pub fn header_frame() -> Vec<u8> {
+    let mut bytes = vec![
+        0xAA, 0x11, 0x00, 0x00, 0x00, 0x07, 0x44, 0x85, 0x36, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    bytes.extend_from_slice(b"PMU Station A 230");
+    bytes.extend_from_slice(&[0x00, 0x00]);
+    refresh_frame(&mut bytes);
+    bytes
+}
@@ -1,11 +1,13 @@
 use test_log::test;
 
 use serde_synphasor::*;
+
+mod common;
 #[test]
 fn base_frame_deserialization() {
-    let bytes: [u8; 16] = [
-        0xAA, 0x41, 0x00, 0x10, 0x00, 0x3c, 0x48, 0x99, 0x90, 0x9a, 0x00, 0x34, 0x2e, 0xd5, 0x23,
-        0x7E,
+    let bytes: [u8; 18] = [
+        0xAA, 0x41, 0x00, 0x12, 0x00, 0x3c, 0x48, 0x99, 0x90, 0x9a, 0x00, 0x34, 0x2e, 0xd5, 0x00,
+        0x05, 0x16, 0x8F,
     ];
 
     let deserialized_message = deserializer::from_bytes(&bytes);
@@ -21,7 +23,7 @@ fn base_frame_deserialization() {
             leap_second_pending: false,
             time_quality: TimeQuality::Locked,
         },
-        data: DataType::Cmd,
+        data: DataType::Cmd(Command::SendCfg2),
     };
 
     assert_eq!(deserialized_message, Ok(message));
@@ -29,9 +31,9 @@ fn base_frame_deserialization() {
 
 #[test]
 fn base_frame_deserialization_error_checksum() {
-    let bytes: [u8; 16] = [
-        0xAA, 0x41, 0x00, 0x10, 0x00, 0x3c, 0x48, 0x99, 0x90, 0x9a, 0x00, 0x34, 0x2e, 0xd5, 0x23,
-        0x7F,
+    let bytes: [u8; 18] = [
+        0xAA, 0x41, 0x00, 0x12, 0x00, 0x3c, 0x48, 0x99, 0x90, 0x9a, 0x00, 0x34, 0x2e, 0xd5, 0x00,
+        0x05, 0x16, 0x8E,
     ];
 
     let deserialized_message = deserializer::from_bytes(&bytes);
@@ -40,9 +42,9 @@ fn base_frame_deserialization_error_checksum() {
 
 #[test]
 fn base_frame_deserialization_error_sync() {
-    let bytes: [u8; 16] = [
-        0xAB, 0x41, 0x00, 0x10, 0x00, 0x3c, 0x48, 0x99, 0x90, 0x9a, 0x00, 0x34, 0x2e, 0xd5, 0x58,
-        0x1F,
+    let bytes: [u8; 18] = [
+        0xAB, 0x41, 0x00, 0x12, 0x00, 0x3c, 0x48, 0x99, 0x90, 0x9a, 0x00, 0x34, 0x2e, 0xd5, 0x00,
+        0x05, 0xBE, 0xAB,
     ];
 
     let deserialized_message = deserializer::from_bytes(&bytes);
@@ -56,9 +58,9 @@ fn base_frame_deserialization_error_sync() {
 
 #[test]
 fn base_frame_deserialization_error_unknown_frame_type() {
-    let bytes: [u8; 16] = [
-        0xAA, 0x62, 0x00, 0x10, 0x00, 0x3c, 0x48, 0x99, 0x90, 0x9a, 0x00, 0x34, 0x2e, 0xd5, 0xFA,
-        0x6F,
+    let bytes: [u8; 18] = [
+        0xAA, 0x62, 0x00, 0x12, 0x00, 0x3c, 0x48, 0x99, 0x90, 0x9a, 0x00, 0x34, 0x2e, 0xd5, 0x00,
+        0x05, 0x68, 0xBE,
     ];
 
     let deserialized_message = deserializer::from_bytes(&bytes);
@@ -68,11 +70,21 @@ fn base_frame_deserialization_error_unknown_frame_type() {
     );
 }
 
+#[cfg(not(feature = "alloc"))]
+#[test]
+fn cfg2_frame_deserialization_requires_alloc() {
+    let bytes = common::annex_cfg2_frame();
+    assert_eq!(
+        deserializer::from_bytes(&bytes),
+        Err(ParseError::AllocRequired)
+    );
+}
+
 #[test]
 fn base_frame_deserialization_error_frame_version() {
-    let bytes: [u8; 16] = [
-        0xAA, 0x43, 0x00, 0x10, 0x00, 0x3c, 0x48, 0x99, 0x90, 0x9a, 0x00, 0x34, 0x2e, 0xd5, 0xA9,
-        0xB8,
+    let bytes: [u8; 18] = [
+        0xAA, 0x43, 0x00, 0x12, 0x00, 0x3c, 0x48, 0x99, 0x90, 0x9a, 0x00, 0x34, 0x2e, 0xd5, 0x00,
+        0x05, 0x5B, 0x56,
     ];
     let deserialized_message = deserializer::from_bytes(&bytes);
     assert_eq!(
@@ -85,9 +97,9 @@ fn base_frame_deserialization_error_frame_version() {
 
 #[test]
 fn base_frame_deserialization_error_unknown_time_quality() {
-    let bytes: [u8; 16] = [
-        0xAA, 0x41, 0x00, 0x10, 0x00, 0x3c, 0x48, 0x99, 0x90, 0x9a, 0x0C, 0x34, 0x2e, 0xd5, 0x6C,
-        0x4C,
+    let bytes: [u8; 18] = [
+        0xAA, 0x41, 0x00, 0x12, 0x00, 0x3c, 0x48, 0x99, 0x90, 0x9a, 0x0C, 0x34, 0x2e, 0xd5, 0x00,
+        0x05, 0x1D, 0x6C,
     ];
 
     let deserialized_message = deserializer::from_bytes(&bytes);
@@ -98,3 +110,564 @@ fn base_frame_deserialization_error_unknown_time_quality() {
         ))
     );
 }
+
+#[cfg(feature = "alloc")]
+#[test]
+fn data_frame_deserialization_error_config_needed() {
+    let bytes: [u8; 16] = [
+        0xAA, 0x01, 0x00, 0x10, 0x00, 0x07, 0x44, 0x85, 0x36, 0x00, 0x00, 0x00, 0x41, 0xB1, 0xDF,
+        0x0E,
+    ];
+
+    assert_eq!(
+        deserializer::from_bytes(&bytes),
+        Err(ParseError::ConfigNeeded)
+    );
+
+    let config = Config {
+        time_base: u24::new(1_000_000).unwrap(),
+        pmus: vec![],
+        data_rate: 30,
+    };
+    let message = deserializer::from_bytes_with_config(&bytes, &config).unwrap();
+    assert_eq!(message.idcode, 7);
+    assert_eq!(message.data, DataType::Data(DataFrame { pmus: vec![] }));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn cfg2_frame_deserialization() {
+    let bytes = common::annex_cfg2_frame();
+
+    assert_eq!(
+        deserializer::from_bytes(&bytes),
+        Ok(common::annex_cfg2_message())
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn cfg1_frame_deserialization() {
+    let mut bytes = common::annex_cfg2_frame();
+    bytes[1] = 0x21;
+    let length = bytes.len();
+    bytes[length - 2..].copy_from_slice(&[0x1D, 0x1F]);
+
+    let mut message = common::annex_cfg2_message();
+    message.data = DataType::Cfg1(common::annex_cfg2_config());
+    assert_eq!(deserializer::from_bytes(&bytes), Ok(message));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn cfg2_frame_deserialization_error_phunit() {
+    let mut bytes = common::annex_cfg2_frame();
+    //Set PHUNIT type of the first phasor to 2
+    bytes[414] = 0x02;
+    let length = bytes.len();
+    bytes[length - 2..].copy_from_slice(&[0xA9, 0xC1]);
+
+    assert_eq!(
+        deserializer::from_bytes(&bytes),
+        Err(ParseError::ConfigParseError(
+            ConfigParseError::UnknownPhasorUnit
+        ))
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn cfg3_frame_deserialization() {
+    let bytes = common::cfg3_frame();
+
+    assert_eq!(deserializer::from_bytes(&bytes), Ok(common::cfg3_message()));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn cfg3_frame_deserialization_error_service_class() {
+    let mut bytes = common::cfg3_frame();
+    //SVC_CLASS of 'X'
+    let svc_class = bytes.len() - 17;
+    bytes[svc_class] = b'X';
+    let length = bytes.len();
+    bytes[length - 2..].copy_from_slice(&[0xB0, 0xF6]);
+
+    assert_eq!(
+        deserializer::from_bytes(&bytes),
+        Err(ParseError::ConfigParseError(
+            ConfigParseError::UnknownServiceClass
+        ))
+    );
+}
+
+#[test]
+fn command_frame_deserialization() {
+    //Turn on transmission
+    let bytes: [u8; 18] = [
+        0xAA, 0x41, 0x00, 0x12, 0x00, 0x07, 0x44, 0x85, 0x36, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x02, 0xB5, 0xBD,
+    ];
+
+    let message = deserializer::from_bytes(&bytes).unwrap();
+    assert_eq!(message.data, DataType::Cmd(Command::TurnOnTransmission));
+}
+
+#[test]
+fn header_frame_deserialization() {
+    let mut bytes = vec![
+        0xAA, 0x11, 0x00, 0x21, 0x00, 0x07, 0x44, 0x85, 0x36, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    bytes.extend_from_slice(b"PMU Station A 230");
+    bytes.extend_from_slice(&[0x79, 0x1F]);
+
+    let message = deserializer::from_bytes(&bytes).unwrap();
+    assert_eq!(
+        message.data,
+        DataType::Header(Header::new("PMU Station A 230"))
+    );
+    let DataType::Header(header) = message.data else {
+        panic!("Expected header frame");
+    };
+    assert_eq!(header.as_str(), Some("PMU Station A 230"));
+}
+
+#[test]
+fn base_frame_deserialization_error_framesize() {
+    let mut bytes = common::annex_cfg2_frame();
+    bytes.push(0x00);
+
+    assert_eq!(
+        deserializer::from_bytes(&bytes),
+        Err(ParseError::BaseParseError(
+            BaseParseError::IncorrectFrameSize
+        ))
+    );
+
+    bytes.truncate(bytes.len() - 2);
+    assert_eq!(
+        deserializer::from_bytes(&bytes),
+        Err(ParseError::BaseParseError(
+            BaseParseError::IncorrectFrameSize
+        ))
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn base_frame_deserialization_trimmed_to_framesize() {
+    let mut bytes = common::annex_cfg2_frame();
+    bytes.extend_from_slice(&[0x00, 0x00, 0xAA]);
+
+    let trimmed = deserializer::trim_to_framesize(&bytes).unwrap();
+    assert_eq!(
+        deserializer::from_bytes(trimmed),
+        Ok(common::annex_cfg2_message())
+    );
+    assert_eq!(
+        deserializer::trim_to_framesize(&bytes[..100]),
+        Err(ParseError::BaseParseError(
+            BaseParseError::IncorrectFrameSize
+        ))
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn cfg2_frame_deserialization_error_trailing_bytes() {
+    let frame = common::annex_cfg2_frame();
+    let mut bytes = frame[..frame.len() - 2].to_vec();
+    bytes.extend_from_slice(&[0x00, 0x00, 0x00, 0x00]);
+    common::refresh_frame(&mut bytes);
+
+    let error = deserializer::SynDeserializer::new(&bytes)
+        .deserialize_message(None)
+        .unwrap_err();
+    assert_eq!(
+        error.error,
+        ParseError::ConfigParseError(ConfigParseError::TrailingBytes)
+    );
+    assert_eq!((error.offset, error.field), (frame.len() - 2, "DATA"));
+}
+
+#[test]
+fn command_frame_deserialization_error_trailing_bytes() {
+    //Send CFG-2 followed by a byte not covered by the command
+    let mut bytes = vec![
+        0xAA, 0x41, 0x00, 0x00, 0x00, 0x3c, 0x48, 0x99, 0x90, 0x9a, 0x00, 0x34, 0x2e, 0xd5, 0x00,
+        0x05, 0x00, 0x00, 0x00,
+    ];
+    common::refresh_frame(&mut bytes);
+    assert_eq!(
+        deserializer::from_bytes(&bytes),
+        Err(ParseError::BaseParseError(
+            BaseParseError::IncorrectFrameSize
+        ))
+    );
+}
+
+#[test]
+fn base_frame_deserialization_error_truncated() {
+    for bytes in [&[][..], &[0xAA], &[0xAA, 0x41, 0x00]] {
+        assert_eq!(
+            deserializer::from_bytes(bytes),
+            Err(ParseError::IllegalAccess)
+        );
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn decode_error_context() {
+    let mut bytes = common::annex_cfg2_frame();
+    //Set PHUNIT type of the first phasor to 2
+    bytes[414] = 0x02;
+    common::refresh_frame(&mut bytes);
+
+    let error = deserializer::SynDeserializer::new(&bytes)
+        .deserialize_message(None)
+        .unwrap_err();
+    assert_eq!(
+        error,
+        DecodeError {
+            error: ParseError::ConfigParseError(ConfigParseError::UnknownPhasorUnit),
+            offset: 414,
+            field: "PHUNIT",
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "PHUNIT is neither voltage nor current at byte 414 (PHUNIT)"
+    );
+    assert_eq!(deserializer::from_bytes_detailed(&bytes), Err(error));
+
+    let bytes: [u8; 18] = [
+        0xAA, 0x41, 0x00, 0x12, 0x00, 0x3c, 0x48, 0x99, 0x90, 0x9a, 0x0C, 0x34, 0x2e, 0xd5, 0x00,
+        0x05, 0x1D, 0x6C,
+    ];
+    let error = deserializer::SynDeserializer::new(&bytes)
+        .deserialize_message(None)
+        .unwrap_err();
+    assert_eq!((error.offset, error.field), (10, "FRACSEC"));
+
+    let mut bytes = common::annex_cfg2_frame();
+    let length = bytes.len();
+    bytes[length - 1] ^= 0xFF;
+    let error = deserializer::SynDeserializer::new(&bytes)
+        .deserialize_message(None)
+        .unwrap_err();
+    assert_eq!(error.error, ParseError::InvalidChecksum);
+    assert_eq!((error.offset, error.field), (length - 2, "CHK"));
+
+    //Data frame cut after STAT and the first phasor
+    let mut bytes = common::annex_data_frame();
+    bytes.truncate(22);
+    common::refresh_frame(&mut bytes);
+    let config = common::annex_cfg2_config();
+    let error = deserializer::from_source_with_config_detailed(&bytes[..], &config).unwrap_err();
+    assert_eq!((error.offset, error.field), (20, "PHASORS"));
+    assert_eq!(
+        deserializer::from_bytes_with_config(&bytes, &config),
+        Err(error.error)
+    );
+}
+
+#[test]
+fn command_frame_sync_interop() {
+    //Send CFG-2 command from a PDC to the PMU with IDCODE 7
+    let mut bytes = common::annex_command_frame();
+    assert_eq!(u16::from_be_bytes([bytes[0], bytes[1]]), 0xAA41);
+    assert_eq!(
+        deserializer::from_bytes(&bytes),
+        Ok(Message {
+            version: FrameVersion::Std2005,
+            idcode: 7,
+            time: Time {
+                soc: 1_149_580_800,
+                fracsec: u24::new(16817).unwrap(),
+                leap_second_direction: false,
+                leap_second_occured: false,
+                leap_second_pending: false,
+                time_quality: TimeQuality::Locked,
+            },
+            data: DataType::Cmd(Command::SendCfg2),
+        })
+    );
+
+    //Same frame with the CFG-3 type code is not a command
+    bytes[1] = 0x51;
+    common::refresh_frame(&mut bytes);
+    assert_eq!(
+        FrameHeader::peek(&bytes).map(|header| header.frame_type),
+        Ok(FrameType::Cfg3)
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn annex_data_frame_deserialization() {
+    let bytes = common::annex_data_frame();
+    let message =
+        deserializer::from_bytes_with_config(&bytes, &common::annex_cfg2_config()).unwrap();
+    assert_eq!(message.version, FrameVersion::Std2005);
+    assert_eq!(message.idcode, 7);
+    assert_eq!(message.time.soc, 1_149_580_800);
+    assert_eq!(message.time.fracsec, u24::new(16817).unwrap());
+    assert_eq!(
+        message.data,
+        DataType::Data(DataFrame {
+            pmus: vec![PmuData {
+                stat_raw: 0x0000,
+                phasors: vec![
+                    Phasor::RectangularInt {
+                        real: 14635,
+                        imaginary: 0,
+                    },
+                    Phasor::RectangularInt {
+                        real: -7318,
+                        imaginary: -12676,
+                    },
+                    Phasor::RectangularInt {
+                        real: -7318,
+                        imaginary: 12675,
+                    },
+                    Phasor::RectangularInt {
+                        real: 1092,
+                        imaginary: 0,
+                    },
+                ],
+                freq: Scalar::Int(2500),
+                dfreq: Scalar::Int(0),
+                analog: vec![
+                    Scalar::Float(100.0),
+                    Scalar::Float(1000.0),
+                    Scalar::Float(10000.0),
+                ],
+                digital: vec![0x3C12],
+            }],
+        })
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn frame_header_peek() {
+    let bytes = common::annex_cfg2_frame();
+    let header = FrameHeader::peek(&bytes[..14]).unwrap();
+    let message = common::annex_cfg2_message();
+
+    assert_eq!(header.frame_type, FrameType::Cfg2);
+    assert_eq!(header.version, message.version);
+    assert_eq!(header.framesize as usize, bytes.len());
+    assert_eq!(header.idcode, message.idcode);
+    assert_eq!(header.time, message.time);
+
+    //Payload and CHK are not checked
+    let mut corrupted = bytes.clone();
+    corrupted[20] ^= 0xFF;
+    assert_eq!(FrameHeader::peek(&corrupted), Ok(header));
+}
+
+#[test]
+fn frame_header_peek_errors() {
+    let bytes = common::annex_cfg2_frame();
+    assert_eq!(
+        FrameHeader::peek(&bytes[..13]),
+        Err(ParseError::IllegalAccess)
+    );
+
+    let mut garbage = bytes.clone();
+    garbage[0] = 0x55;
+    assert_eq!(
+        FrameHeader::peek(&garbage),
+        Err(ParseError::BaseParseError(
+            BaseParseError::IncorrectSyncWord
+        ))
+    );
+
+    let mut unknown_type = bytes.clone();
+    unknown_type[1] = 0x61;
+    assert_eq!(
+        FrameHeader::peek(&unknown_type),
+        Err(ParseError::BaseParseError(BaseParseError::UnknownFrameType))
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn data_frame_ref_errors() {
+    let config = common::annex_cfg2_config();
+    let bytes = common::annex_data_frame();
+    let frame = deserializer::DataFrameRef::new(&bytes, &config).unwrap();
+    assert_eq!(frame.pmu(0).unwrap().digital(0), Some(0x3C12));
+
+    assert_eq!(
+        deserializer::DataFrameRef::new(&common::annex_cfg2_frame(), &config).map(|_| ()),
+        Err(ParseError::BaseParseError(
+            BaseParseError::UnexpectedFrameType
+        ))
+    );
+
+    let mut corrupted = bytes.clone();
+    corrupted[20] ^= 0xFF;
+    assert_eq!(
+        deserializer::DataFrameRef::new(&corrupted, &config).map(|_| ()),
+        Err(ParseError::InvalidChecksum)
+    );
+
+    //Configuration with one analog channel fewer than the frame carries
+    let mut mismatched = config.clone();
+    mismatched.pmus[0].analogs.pop();
+    assert_eq!(
+        deserializer::DataFrameRef::new(&bytes, &mismatched).map(|_| ()),
+        Err(ParseError::ConfigMismatch)
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn fracsec_exceeds_time_base() {
+    //FRACSEC of 1_000_000 with a TIME_BASE of 1_000_000
+    let mut bytes = common::annex_cfg2_frame();
+    bytes[11..14].copy_from_slice(&[0x0F, 0x42, 0x40]);
+    common::refresh_frame(&mut bytes);
+    let error = deserializer::SynDeserializer::new(&bytes)
+        .deserialize_message(None)
+        .unwrap_err();
+    assert_eq!(
+        error.error,
+        ParseError::BaseParseError(BaseParseError::FracsecExceedsTimeBase)
+    );
+    assert_eq!((error.offset, error.field), (10, "FRACSEC"));
+
+    let mut bytes = common::annex_data_frame();
+    bytes[11..14].copy_from_slice(&[0x0F, 0x42, 0x40]);
+    common::refresh_frame(&mut bytes);
+    assert_eq!(
+        deserializer::from_bytes_with_config(&bytes, &common::annex_cfg2_config()),
+        Err(ParseError::BaseParseError(
+            BaseParseError::FracsecExceedsTimeBase
+        ))
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn io_source_deserialization() {
+    use deserializer::IoSource;
+    let config = common::annex_cfg2_config();
+    let stream = [
+        common::annex_cfg2_frame(),
+        common::header_frame(),
+        common::annex_data_frame(),
+    ]
+    .concat();
+    let mut reader = stream.as_slice();
+
+    let message = deserializer::from_source(IoSource::new(&mut reader, &mut [])).unwrap();
+    assert_eq!(message, common::annex_cfg2_message());
+
+    //Header text is borrowed from the scratch buffer
+    let mut scratch = [0u8; 32];
+    let message = deserializer::from_source(IoSource::new(&mut reader, &mut scratch)).unwrap();
+    assert_eq!(
+        message.data,
+        DataType::Header(Header::new("PMU Station A 230"))
+    );
+
+    assert_eq!(
+        deserializer::from_source_with_config(IoSource::new(&mut reader, &mut []), &config),
+        deserializer::from_bytes_with_config(&common::annex_data_frame(), &config)
+    );
+    assert!(reader.is_empty());
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn io_source_cfg3_without_scratch() {
+    use deserializer::IoSource;
+    use serde_synphasor::config::{Cfg3Fragment, Cfg3Payload};
+
+    //UTF-8 names and fragments are owned and need no scratch buffer
+    let frame = common::cfg3_frame();
+    assert_eq!(
+        deserializer::from_source(IoSource::new(frame.as_slice(), &mut [])),
+        Ok(common::cfg3_message())
+    );
+
+    let mut fragment = frame.clone();
+    fragment[14..16].copy_from_slice(&[0x00, 0x01]);
+    common::refresh_frame(&mut fragment);
+    assert_eq!(
+        deserializer::from_source(IoSource::new(fragment.as_slice(), &mut []))
+            .map(|message| message.data),
+        Ok(DataType::Cfg3(Cfg3Payload::Fragment(Cfg3Fragment {
+            cont_idx: 1,
+            bytes: frame[16..frame.len() - 2].to_vec(),
+        })))
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn io_source_errors() {
+    use deserializer::IoSource;
+
+    struct FailingReader;
+    impl std::io::Read for FailingReader {
+        fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+            Err(std::io::ErrorKind::ConnectionReset.into())
+        }
+    }
+
+    let header = common::header_frame();
+    let mut scratch = [0u8; 8];
+    assert_eq!(
+        deserializer::from_source(IoSource::new(header.as_slice(), &mut scratch)),
+        Err(ParseError::SpaceExceeded)
+    );
+
+    let frame = common::annex_cfg2_frame();
+    assert_eq!(
+        deserializer::from_source(IoSource::new(&frame[..100], &mut [])),
+        Err(ParseError::IllegalAccess)
+    );
+
+    let mut corrupted = frame.clone();
+    corrupted[20] ^= 0xFF;
+    let error =
+        deserializer::SynDeserializer::from_source(IoSource::new(corrupted.as_slice(), &mut []))
+            .deserialize_message(None)
+            .unwrap_err();
+    assert_eq!(error.error, ParseError::InvalidChecksum);
+    assert_eq!((error.offset, error.field), (frame.len() - 2, "CHK"));
+
+    assert_eq!(
+        deserializer::from_source(IoSource::new(FailingReader, &mut [])),
+        Err(ParseError::Io)
+    );
+}
+
+#[cfg(all(feature = "embedded-io", feature = "alloc"))]
+#[test]
+fn embedded_io_source_deserialization() {
+    use deserializer::EmbeddedIoSource;
+    let stream = [common::header_frame(), common::annex_cfg2_frame()].concat();
+    let mut reader = stream.as_slice();
+
+    let mut scratch = [0u8; 32];
+    let message =
+        deserializer::from_source(EmbeddedIoSource::new(&mut reader, &mut scratch)).unwrap();
+    assert_eq!(
+        message.data,
+        DataType::Header(Header::new("PMU Station A 230"))
+    );
+    assert_eq!(
+        deserializer::from_source(EmbeddedIoSource::new(&mut reader, &mut [])),
+        Ok(common::annex_cfg2_message())
+    );
+    assert_eq!(
+        deserializer::from_source(EmbeddedIoSource::new(&mut reader, &mut [])),
+        Err(ParseError::IllegalAccess)
+    );
+}
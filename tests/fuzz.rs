@@ -0,0 +1,202 @@
+#![cfg(feature = "alloc")]
+/*!
+ * Property tests feeding truncated, corrupted and random input to the decoder.
+ * Every input must be answered with a message or a `ParseError`, never a panic.
+ */
+use proptest::prelude::*;
+use serde_synphasor::*;
+
+mod common;
+
+fn valid_frames() -> Vec<Vec<u8>> {
+    let mut header = vec![
+        0xAA, 0x11, 0x00, 0x00, 0x00, 0x07, 0x44, 0x85, 0x36, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+    header.extend_from_slice(b"PMU Station A 230\0\0");
+    common::refresh_frame(&mut header);
+    let mut command = vec![
+        0xAA, 0x41, 0x00, 0x00, 0x00, 0x07, 0x44, 0x85, 0x36, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x08, 0x01, 0x02, 0x00, 0x00,
+    ];
+    common::refresh_frame(&mut command);
+    vec![
+        common::annex_cfg2_frame(),
+        common::cfg3_frame(),
+        common::annex_data_frame(),
+        header,
+        command,
+    ]
+}
+
+fn decode(bytes: &[u8]) {
+    if let Err(e) = deserializer::SynDeserializer::new(bytes).deserialize_message(None) {
+        let _ = e.to_string();
+    }
+    if let Ok(message) = deserializer::from_bytes(bytes) {
+        let header = FrameHeader::peek(bytes).unwrap();
+        assert_eq!(header.frame_type, message.data.frame_type());
+        assert_eq!((header.idcode, header.time), (message.idcode, message.time));
+        #[cfg(feature = "std")]
+        {
+            let mut scratch = vec![0u8; bytes.len()];
+            let source = deserializer::IoSource::new(bytes, &mut scratch);
+            assert_eq!(
+                format!("{:?}", deserializer::from_source(source).map(|m| m.data)),
+                format!("{:?}", Ok::<_, ParseError>(message.data))
+            );
+        }
+    }
+    let config = common::annex_cfg2_config();
+    let message = deserializer::from_bytes_with_config(bytes, &config);
+    if let Ok(frame) = deserializer::DataFrameRef::new(bytes, &config) {
+        let Ok(Message {
+            data: DataType::Data(data),
+            ..
+        }) = message
+        else {
+            panic!("View accepted a frame the decoder rejects");
+        };
+        for (view, pmu) in frame.pmus().zip(&data.pmus) {
+            assert_eq!(view.stat(), pmu.stat());
+            //Compared as text as NaN differs from itself
+            assert_eq!(
+                format!("{:?}", view.phasor(0)),
+                format!("{:?}", pmu.phasors.first())
+            );
+            assert_eq!(
+                format!("{:?}", view.analog(0)),
+                format!("{:?}", pmu.analog.first())
+            );
+        }
+    }
+    if let Ok(trimmed) = deserializer::trim_to_framesize(bytes) {
+        let _ = deserializer::from_bytes(trimmed);
+    }
+}
+
+#[test]
+fn valid_frames_decode() {
+    for bytes in valid_frames() {
+        assert!(deserializer::from_bytes_with_config(&bytes, &common::annex_cfg2_config()).is_ok());
+    }
+}
+
+proptest! {
+    #[test]
+    fn random_bytes(bytes in proptest::collection::vec(any::<u8>(), 0..512)) {
+        decode(&bytes);
+    }
+
+    #[test]
+    fn random_payload(
+        frame_type in 0u8..8,
+        payload in proptest::collection::vec(any::<u8>(), 0..512),
+    ) {
+        let mut bytes = vec![
+            0xAA, (frame_type << 4) | 0x02, 0x00, 0x00, 0x00, 0x07, 0x44, 0x85, 0x36, 0x00, 0x00,
+            0x00, 0x00, 0x00,
+        ];
+        bytes.extend_from_slice(&payload);
+        bytes.extend_from_slice(&[0x00, 0x00]);
+        common::refresh_frame(&mut bytes);
+        decode(&bytes);
+    }
+
+    #[test]
+    fn truncated_frames(frame in 0usize..5, length in 0usize..512, refresh in any::<bool>()) {
+        let mut bytes = valid_frames().swap_remove(frame);
+        bytes.truncate(length);
+        if refresh {
+            common::refresh_frame(&mut bytes);
+        }
+        decode(&bytes);
+    }
+
+    #[test]
+    fn corrupted_frames(
+        frame in 0usize..5,
+        edits in proptest::collection::vec((any::<usize>(), any::<u8>()), 1..8),
+        refresh in any::<bool>(),
+    ) {
+        let mut bytes = valid_frames().swap_remove(frame);
+        let length = bytes.len();
+        for (index, value) in edits {
+            bytes[index % length] = value;
+        }
+        if refresh {
+            common::refresh_frame(&mut bytes);
+        }
+        decode(&bytes);
+    }
+
+    #[test]
+    fn random_cfg3_fragments(
+        fragments in proptest::collection::vec(
+            (prop_oneof![Just(1u16), Just(2), Just(0xFFFF), any::<u16>()],
+             proptest::collection::vec(any::<u8>(), 0..64)),
+            0..8,
+        ),
+    ) {
+        let mut assembler = config::Cfg3Assembler::new();
+        for (cont_idx, bytes) in fragments {
+            let _ = assembler.push(config::Cfg3Payload::Fragment(config::Cfg3Fragment {
+                cont_idx,
+                bytes,
+            }));
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn frame_decoder_recovers_frames(
+        frames in proptest::collection::vec(
+            (0usize..5, proptest::collection::vec(0u8..0xAA, 0..32)),
+            0..8,
+        ),
+        chunk in 1usize..64,
+    ) {
+        let valid = valid_frames();
+        let mut stream = vec![];
+        let mut expected = vec![];
+        let mut garbage = 0;
+        for (frame, noise) in frames {
+            garbage += noise.len();
+            stream.extend_from_slice(&noise);
+            stream.extend_from_slice(&valid[frame]);
+            expected.push(valid[frame].clone());
+        }
+
+        let mut buffer = [0u8; 1024];
+        let mut decoder = decoder::FrameDecoder::new(&mut buffer);
+        let mut received = vec![];
+        for chunk in stream.chunks(chunk) {
+            let mut chunk = chunk;
+            while !chunk.is_empty() {
+                let taken = decoder.push(chunk);
+                chunk = &chunk[taken..];
+                while let Some(frame) = decoder.next_frame() {
+                    received.push(frame.to_vec());
+                }
+            }
+        }
+        prop_assert_eq!(received, expected);
+        prop_assert_eq!(decoder.discarded(), garbage);
+    }
+
+    #[test]
+    fn frame_decoder_random_bytes(
+        bytes in proptest::collection::vec(any::<u8>(), 0..1024),
+        chunk in 1usize..64,
+        capacity in 0usize..512,
+    ) {
+        let mut buffer = vec![0u8; capacity];
+        let mut decoder = decoder::FrameDecoder::new(&mut buffer);
+        for chunk in bytes.chunks(chunk) {
+            decoder.push(chunk);
+            while let Some(frame) = decoder.next_frame() {
+                decode(frame);
+            }
+        }
+    }
+}
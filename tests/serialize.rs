@@ -1,37 +1,11 @@
+#![cfg(feature = "alloc")]
 use test_log::test;
 
-use serde_synphasor::{serializer::ByteContainer, *};
+use serde_synphasor::{config::*, serializer::ByteContainer, *};
 
-/**
- * Dynamically allocated ByteContainer for tests
-*/
-struct VecContainer {
-    bytes: std::vec::Vec<u8>,
-    index: usize,
-}
-
-impl VecContainer {
-    pub fn new() -> VecContainer {
-        VecContainer {
-            bytes: vec![],
-            index: 0,
-        }
-    }
-}
-impl ByteContainer for VecContainer {
-    fn enque(&mut self, v: u8) -> Result<(), error::SerializeError> {
-        self.bytes.push(v);
-        self.index += 1;
-        Ok(())
-    }
-
-    fn get(&self) -> &[u8] {
-        &self.bytes[0..self.index]
-    }
-}
+mod common;
 
 /// Tests Basic Baseframe Serialization.
-/// Valid checksum validation is ignored in test
 #[test]
 fn base_frame_serialization() {
     let message = Message {
@@ -45,16 +19,487 @@ fn base_frame_serialization() {
             leap_second_pending: false,
             time_quality: TimeQuality::Locked,
         },
-        data: DataType::Cmd,
+        data: DataType::Cmd(Command::SendCfg2),
     };
 
-    let bytes = VecContainer::new();
+    let bytes = Vec::new();
 
     let serializer = SynSerializer::new(bytes);
 
-    //Ignore checksum, only include first 14 bytes
     assert_eq!(
-        [0xAA, 0x41, 0x00, 0x10, 0x00, 0x3c, 0x48, 0x99, 0x90, 0x9a, 0x00, 0x34, 0x2e, 0xd5],
-        serializer.to_bytes(&message).unwrap().get()[..14]
+        [
+            0xAA, 0x41, 0x00, 0x12, 0x00, 0x3c, 0x48, 0x99, 0x90, 0x9a, 0x00, 0x34, 0x2e, 0xd5,
+            0x00, 0x05, 0x16, 0x8F
+        ],
+        serializer.to_bytes(&message).unwrap().get()
+    );
+}
+
+fn data_message() -> Message<'static> {
+    Message {
+        version: FrameVersion::Std2011,
+        idcode: 7,
+        time: Time {
+            soc: 1_149_580_800,
+            fracsec: u24::new(16817).unwrap(),
+            leap_second_direction: false,
+            leap_second_occured: false,
+            leap_second_pending: false,
+            time_quality: TimeQuality::Locked,
+        },
+        data: DataType::Data(DataFrame {
+            pmus: vec![
+                PmuData {
+                    stat_raw: 0x0000,
+                    phasors: vec![
+                        Phasor::RectangularInt {
+                            real: 14635,
+                            imaginary: 0,
+                        },
+                        Phasor::RectangularInt {
+                            real: -7318,
+                            imaginary: -12676,
+                        },
+                    ],
+                    freq: Scalar::Int(2500),
+                    dfreq: Scalar::Int(0),
+                    analog: vec![Scalar::Int(100)],
+                    digital: vec![0x3C12],
+                },
+                PmuData {
+                    stat_raw: 0x0800,
+                    phasors: vec![Phasor::PolarFloat {
+                        magnitude: 120.5,
+                        angle: -1.5,
+                    }],
+                    freq: Scalar::Float(60.01),
+                    dfreq: Scalar::Float(-0.25),
+                    analog: vec![],
+                    digital: vec![],
+                },
+            ],
+        }),
+    }
+}
+
+fn pmu_config(station: &str, idcode: u16, format: Format, phnmr: usize, annmr: usize) -> PmuConfig {
+    PmuConfig {
+        station: Name::new(station).unwrap(),
+        idcode,
+        format,
+        phasors: (0..phnmr)
+            .map(|i| PhasorChannel {
+                name: Name::new(["VA", "VB", "VC", "IA"][i]).unwrap(),
+                unit: PhasorUnit {
+                    kind: PhasorKind::Voltage,
+                    factor: u24::new(915527).unwrap(),
+                },
+            })
+            .collect(),
+        analogs: (0..annmr)
+            .map(|_| AnalogChannel {
+                name: Name::new("ANALOG1").unwrap(),
+                unit: AnalogUnit {
+                    kind: AnalogKind::PointOnWave,
+                    factor: i24::new(1).unwrap(),
+                },
+            })
+            .collect(),
+        digitals: vec![],
+        fnom: NominalFrequency::Hz60,
+        cfgcnt: 22,
+    }
+}
+
+fn data_config() -> Config {
+    let mut int_pmu = pmu_config(
+        "Station A",
+        7,
+        Format {
+            freq: NumberFormat::Int,
+            analog: NumberFormat::Int,
+            phasor: NumberFormat::Int,
+            phasor_notation: PhasorNotation::Rectangular,
+        },
+        2,
+        1,
+    );
+    int_pmu.digitals.push(DigitalChannel {
+        names: core::array::from_fn(|i| Name::new(&format!("BREAKER {}", i)).unwrap()),
+        unit: DigitalUnit {
+            normal: 0x0000,
+            valid: 0xFFFF,
+        },
+    });
+    let float_pmu = pmu_config(
+        "Station B",
+        8,
+        Format {
+            freq: NumberFormat::Float,
+            analog: NumberFormat::Float,
+            phasor: NumberFormat::Float,
+            phasor_notation: PhasorNotation::Polar,
+        },
+        1,
+        0,
+    );
+    Config {
+        time_base: u24::new(1_000_000).unwrap(),
+        pmus: vec![int_pmu, float_pmu],
+        data_rate: 30,
+    }
+}
+
+/// Tests data frame payload encoding of integer and floating point blocks
+#[test]
+fn data_frame_payload_serialization() {
+    let serializer = SynSerializer::new(Vec::new());
+    let bytes = serializer.to_bytes(&data_message()).unwrap();
+    let bytes = bytes.get();
+
+    assert_eq!(
+        [
+            0x00, 0x00, 0x39, 0x2B, 0x00, 0x00, 0xE3, 0x6A, 0xCE, 0x7C, 0x09, 0xC4, 0x00, 0x00,
+            0x00, 0x64, 0x3C, 0x12, 0x08, 0x00, 0x42, 0xF1, 0x00, 0x00, 0xBF, 0xC0, 0x00, 0x00,
+            0x42, 0x70, 0x0A, 0x3D, 0xBE, 0x80, 0x00, 0x00
+        ],
+        bytes[14..bytes.len() - 2]
+    );
+}
+
+/// Tests data frames decode back to the same message given their configuration
+#[test]
+fn data_frame_round_trip() {
+    let message = data_message();
+    let serializer = SynSerializer::new(Vec::new());
+    let bytes = serializer.to_bytes(&message).unwrap();
+
+    assert_eq!(
+        deserializer::from_bytes_with_config(bytes.get(), &data_config()),
+        Ok(message)
+    );
+    assert_eq!(
+        deserializer::from_bytes(bytes.get()),
+        Err(ParseError::ConfigNeeded)
+    );
+}
+
+/// Tests configuration frames are emitted as they were received
+#[test]
+fn cfg2_frame_round_trip() {
+    let bytes = common::annex_cfg2_frame();
+    let message = deserializer::from_bytes(&bytes).unwrap();
+
+    let serializer = SynSerializer::new(Vec::new());
+    let serialized = serializer.to_bytes(&message).unwrap();
+
+    assert_eq!(serialized.get(), bytes);
+}
+
+/// Tests CFG-3 frames are emitted as they were received
+#[test]
+fn cfg3_frame_round_trip() {
+    let bytes = common::cfg3_frame();
+    let message = deserializer::from_bytes(&bytes).unwrap();
+
+    let serializer = SynSerializer::new(Vec::new());
+    let serialized = serializer.to_bytes(&message).unwrap();
+
+    assert_eq!(serialized.get(), bytes);
+}
+
+/// Tests a configuration split over several CFG-3 frames is reassembled
+#[test]
+fn cfg3_fragmented_round_trip() {
+    let config = common::cfg3_config();
+    let payloads = config.split(64).unwrap();
+    assert_eq!(payloads.len(), 3);
+
+    let mut assembler = Cfg3Assembler::new();
+    let mut reassembled = None;
+    for payload in payloads {
+        let mut message = common::cfg3_message();
+        message.data = DataType::Cfg3(payload);
+        let serializer = SynSerializer::new(Vec::new());
+        let bytes = serializer.to_bytes(&message).unwrap();
+
+        let message = deserializer::from_bytes(bytes.get()).unwrap();
+        let DataType::Cfg3(payload) = message.data else {
+            panic!("Expected CFG-3 frame");
+        };
+        assert_eq!(reassembled, None);
+        reassembled = assembler.push(payload).unwrap();
+    }
+    assert_eq!(reassembled, Some(config));
+}
+
+/// Tests CFG-3 fragments are reassembled while decoding frame by frame
+#[test]
+fn cfg3_fragmented_frames_decoded() {
+    let config = common::cfg3_config();
+    let mut assembler = Cfg3Assembler::new();
+    let frames: Vec<_> = config
+        .split(64)
+        .unwrap()
+        .into_iter()
+        .map(|payload| {
+            let mut message = common::cfg3_message();
+            message.data = DataType::Cfg3(payload);
+            let serializer = SynSerializer::new(Vec::new());
+            serializer.to_bytes(&message).unwrap().get().to_vec()
+        })
+        .collect();
+    let decoded: Vec<_> = frames
+        .iter()
+        .map(|frame| assembler.decode(frame, None).unwrap())
+        .collect();
+    assert_eq!(decoded, [None, None, Some(common::cfg3_message())]);
+
+    //Other frames pass through
+    let header = common::header_frame();
+    assert_eq!(
+        assembler.decode(&header, None),
+        deserializer::from_bytes(&header).map(Some)
     );
 }
+
+/// Tests FrameDecoder reassembles CFG-3 fragments read from a stream
+#[test]
+fn cfg3_fragments_reassembled_from_stream() {
+    let config = common::cfg3_config();
+    let mut stream = common::header_frame();
+    for payload in config.split(64).unwrap() {
+        let mut message = common::cfg3_message();
+        message.data = DataType::Cfg3(payload);
+        let serializer = SynSerializer::new(stream);
+        stream = serializer.to_bytes(&message).unwrap();
+    }
+
+    let mut buffer = [0u8; 256];
+    let mut decoder = decoder::FrameDecoder::new(&mut buffer);
+    let mut frame_types = Vec::new();
+    let mut reassembled = None;
+    for mut chunk in stream.chunks(32) {
+        while !chunk.is_empty() {
+            let taken = decoder.push(chunk);
+            chunk = &chunk[taken..];
+            while let Some(message) = decoder.next_message(None) {
+                let data = message.unwrap().data;
+                frame_types.push(data.frame_type());
+                if let DataType::Cfg3(Cfg3Payload::Config(config)) = data {
+                    reassembled = Some(config);
+                }
+            }
+        }
+    }
+    assert_eq!(frame_types, [FrameType::Header, FrameType::Cfg3]);
+    assert_eq!(reassembled, Some(config));
+}
+
+/// Tests extended command frames carry their user data both ways
+#[test]
+fn extended_command_round_trip() {
+    let mut message = common::annex_cfg2_message();
+    message.data = DataType::Cmd(Command::Extended(&[0xDE, 0xAD, 0xBE, 0xEF]));
+
+    let serializer = SynSerializer::new(Vec::new());
+    let bytes = serializer.to_bytes(&message).unwrap();
+    let bytes = bytes.get();
+
+    assert_eq!([0x00, 0x08, 0xDE, 0xAD, 0xBE, 0xEF], bytes[14..20]);
+    assert_eq!(deserializer::from_bytes(bytes), Ok(message));
+}
+
+/// Tests header frames carry their text both ways
+#[test]
+fn header_round_trip() {
+    let mut message = common::annex_cfg2_message();
+    message.data = DataType::Header(Header::from(String::from("PMU Station A 230")));
+
+    let serializer = SynSerializer::new(Vec::new());
+    let bytes = serializer.to_bytes(&message).unwrap();
+    let bytes = bytes.get();
+
+    assert_eq!([0xAA, 0x11], bytes[..2]);
+    assert_eq!(b"PMU Station A 230", &bytes[14..31]);
+    assert_eq!(deserializer::from_bytes(bytes), Ok(message));
+}
+
+/// Tests FRAMESIZE counts every byte of the emitted frame, CHK included
+#[test]
+fn framesize_matches_frame_length() {
+    let mut header = common::annex_cfg2_message();
+    header.data = DataType::Header(Header::new("PMU Station A 230"));
+    let mut command = common::annex_cfg2_message();
+    command.data = DataType::Cmd(Command::Extended(&[0x01, 0x02, 0x03]));
+
+    for message in [
+        data_message(),
+        header,
+        command,
+        common::annex_cfg2_message(),
+        common::cfg3_message(),
+    ] {
+        let serializer = SynSerializer::new(Vec::new());
+        let bytes = serializer.to_bytes(&message).unwrap();
+        let bytes = bytes.get();
+
+        assert_eq!(
+            u16::from_be_bytes([bytes[2], bytes[3]]) as usize,
+            bytes.len()
+        );
+    }
+}
+
+/// Tests SYNC carries the frame type codes of IEEE C37.118.2 Table 2:
+/// 000 data, 001 header, 010 CFG-1, 011 CFG-2, 100 command, 101 CFG-3
+#[test]
+fn frame_type_codes() {
+    let with_data = |data: DataType<'static>| {
+        let mut message = common::cfg3_message();
+        message.data = data;
+        message
+    };
+    let messages = [
+        (
+            0xAA02,
+            with_data(DataType::Data(DataFrame { pmus: vec![] })),
+        ),
+        (0xAA12, with_data(DataType::Header(Header::new("PMU")))),
+        (
+            0xAA22,
+            with_data(DataType::Cfg1(common::annex_cfg2_config())),
+        ),
+        (
+            0xAA32,
+            with_data(DataType::Cfg2(common::annex_cfg2_config())),
+        ),
+        (0xAA42, with_data(DataType::Cmd(Command::SendCfg3))),
+        (0xAA52, common::cfg3_message()),
+    ];
+
+    for (sync, message) in messages {
+        let serializer = SynSerializer::new(Vec::new());
+        let bytes = serializer.to_bytes(&message).unwrap();
+        let bytes = bytes.get();
+
+        assert_eq!(u16::from_be_bytes([bytes[0], bytes[1]]), sync);
+        assert_eq!(
+            deserializer::from_bytes_with_config(
+                bytes,
+                &Config {
+                    time_base: u24::new(1_000_000).unwrap(),
+                    pmus: vec![],
+                    data_rate: 30,
+                }
+            ),
+            Ok(message)
+        );
+    }
+}
+
+/// Tests borrowed data frame views decode the same values as the message
+#[test]
+fn data_frame_ref_matches_message() {
+    let message = data_message();
+    let serializer = SynSerializer::new(Vec::new());
+    let bytes = serializer.to_bytes(&message).unwrap();
+    let config = data_config();
+
+    let frame = deserializer::DataFrameRef::new(bytes.get(), &config).unwrap();
+    assert_eq!(frame.header().idcode, message.idcode);
+    assert_eq!(frame.header().time, message.time);
+    let DataType::Data(data) = message.data else {
+        panic!("Expected data frame");
+    };
+    assert_eq!(frame.num_pmu(), data.pmus.len());
+    assert_eq!(frame.pmu(2).map(|pmu| pmu.stat_raw()), None);
+
+    for (index, pmu) in data.pmus.iter().enumerate() {
+        let view = frame.pmu(index).unwrap();
+        assert_eq!(view.stat(), pmu.stat());
+        assert_eq!(view.freq(), pmu.freq);
+        assert_eq!(view.dfreq(), pmu.dfreq);
+        for (i, phasor) in pmu.phasors.iter().enumerate() {
+            assert_eq!(view.phasor(i).as_ref(), Some(phasor));
+        }
+        assert_eq!(view.phasor(pmu.phasors.len()), None);
+        for (i, analog) in pmu.analog.iter().enumerate() {
+            assert_eq!(view.analog(i).as_ref(), Some(analog));
+        }
+        assert_eq!(view.analog(pmu.analog.len()), None);
+        for (i, digital) in pmu.digital.iter().enumerate() {
+            assert_eq!(view.digital(i), Some(*digital));
+        }
+        assert_eq!(view.digital(pmu.digital.len()), None);
+    }
+    assert_eq!(
+        frame.pmus().map(|pmu| pmu.stat_raw()).collect::<Vec<_>>(),
+        [0x0000, 0x0800]
+    );
+    assert!(frame.pmu(1).unwrap().stat().trigger_detected);
+    assert_eq!(frame.pmu(0).unwrap().frequency(), 62.5);
+    assert_eq!(frame.pmu(1).unwrap().rocof(), -0.25);
+}
+
+/// Tests PMU blocks of a concentrated stream pair with their configuration
+#[test]
+fn data_frame_blocks_pair_with_config() {
+    let DataType::Data(data) = data_message().data else {
+        panic!("Expected data frame");
+    };
+    let config = data_config();
+
+    let blocks = data.blocks(&config).unwrap().collect::<Vec<_>>();
+    assert_eq!(blocks.len(), 2);
+    assert_eq!(
+        blocks
+            .iter()
+            .map(|(pmu, data)| (pmu.idcode, data.phasors.len()))
+            .collect::<Vec<_>>(),
+        [(7, 2), (8, 1)]
+    );
+    assert_eq!(data.pmu(&config, 8), Some(&data.pmus[1]));
+    assert_eq!(data.pmu(&config, 9), None);
+
+    //Second block carries floating point values
+    let mut mismatched = config.clone();
+    mismatched.pmus[1].format.freq = NumberFormat::Int;
+    assert_eq!(
+        data.blocks(&mismatched).map(|_| ()),
+        Err(ParseError::ConfigMismatch)
+    );
+    mismatched.pmus.pop();
+    assert_eq!(
+        data.blocks(&mismatched).map(|_| ()),
+        Err(ParseError::ConfigMismatch)
+    );
+}
+
+/// Tests frames encode into fixed buffers and fail once they are full
+#[test]
+fn fixed_buffer_serialization() {
+    let message = common::annex_cfg2_message();
+    let expected = common::annex_cfg2_frame();
+
+    let mut buffer = [0u8; 512];
+    let serializer = SynSerializer::new(serializer::Cursor::new(&mut buffer[..]));
+    assert_eq!(serializer.to_bytes(&message).unwrap().get(), expected);
+
+    let serializer = SynSerializer::new(serializer::Cursor::new([0u8; 64]));
+    assert_eq!(
+        serializer.to_bytes(&message).map(|_| ()),
+        Err(error::SerializeError::SpaceExceeded)
+    );
+}
+
+#[test]
+fn frames_appended_to_buffer() {
+    let message = common::annex_cfg2_message();
+    let expected = common::annex_cfg2_frame();
+
+    //FRAMESIZE and CHK only cover the frame being written
+    let bytes = SynSerializer::new(Vec::new()).to_bytes(&message).unwrap();
+    let bytes = SynSerializer::new(bytes).to_bytes(&message).unwrap();
+    assert_eq!(bytes, [expected.as_slice(), expected.as_slice()].concat());
+}